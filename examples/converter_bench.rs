@@ -0,0 +1,74 @@
+//!
+//! Compares the cached `Converter` against the free functions in `calc`.
+//!
+//! Run with `cargo run --release --example converter_bench`.
+//!
+
+extern crate time_calc;
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use time_calc::{
+    samples_from_ticks,
+    ticks_from_samples,
+    Bpm,
+    Converter,
+    Ppqn,
+    SampleHz,
+    Samples,
+    Ticks,
+};
+
+const SAMPLE_HZ: SampleHz = 48_000.0;
+const PPQN: Ppqn = 960;
+const BPM: Bpm = 128.0;
+const EVENTS: usize = 4_096;
+const ROUNDS: usize = 1_000;
+
+fn time<F: FnMut()>(mut f: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        f();
+    }
+    start.elapsed()
+}
+
+fn report(name: &str, free: Duration, cached: Duration) {
+    let per_event = |d: Duration| d.as_nanos() as f64 / (EVENTS * ROUNDS) as f64;
+    println!("{}:", name);
+    println!("    calc free functions: {:.2}ns per event", per_event(free));
+    println!("    Converter:           {:.2}ns per event", per_event(cached));
+    println!("    speedup:             {:.2}x", free.as_secs_f64() / cached.as_secs_f64());
+}
+
+fn main() {
+    let converter = Converter::new(BPM, PPQN, SAMPLE_HZ);
+    let samples: Vec<Samples> = (0..EVENTS as i64).map(|i| Samples(i * 37)).collect();
+    let ticks: Vec<Ticks> = (0..EVENTS as i64).map(|i| Ticks(i * 13)).collect();
+    let mut ticks_out = vec![Ticks(0); EVENTS];
+    let mut samples_out = vec![Samples(0); EVENTS];
+
+    let free = time(|| {
+        for (d, s) in ticks_out.iter_mut().zip(&samples) {
+            *d = Ticks(ticks_from_samples(black_box(s.samples()), BPM, PPQN, SAMPLE_HZ));
+        }
+        black_box(&ticks_out);
+    });
+    let cached = time(|| {
+        converter.ticks_from_samples_slice(black_box(&samples), &mut ticks_out);
+        black_box(&ticks_out);
+    });
+    report("Samples -> Ticks", free, cached);
+
+    let free = time(|| {
+        for (d, t) in samples_out.iter_mut().zip(&ticks) {
+            *d = Samples(samples_from_ticks(black_box(t.ticks()), BPM, PPQN, SAMPLE_HZ));
+        }
+        black_box(&samples_out);
+    });
+    let cached = time(|| {
+        converter.samples_from_ticks_slice(black_box(&ticks), &mut samples_out);
+        black_box(&samples_out);
+    });
+    report("Ticks -> Samples", free, cached);
+}
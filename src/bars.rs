@@ -6,6 +6,7 @@
 //!
 
 use num::{FromPrimitive, ToPrimitive};
+use std::iter::Sum;
 use std::ops::{Add, Sub, Mul, Div, Rem, Neg,
                AddAssign, SubAssign, MulAssign, DivAssign, RemAssign};
 use super::calc;
@@ -47,23 +48,23 @@ impl Bars {
 
     /// Convert to the unit value of `Ms`.
     #[inline]
-    pub fn ms(&self, bpm: Bpm, ts: TimeSig) -> calc::Ms {
+    pub fn ms(&self, bpm: impl Into<Bpm>, ts: TimeSig) -> calc::Ms {
         ms_from_measure(self.bars(), Division::Bar, DivType::Whole, bpm, ts)
     }
     /// Convert to `Ms`.
     #[inline]
-    pub fn to_ms(&self, bpm: Bpm, ts: TimeSig) -> Ms {
+    pub fn to_ms(&self, bpm: impl Into<Bpm>, ts: TimeSig) -> Ms {
         Ms(self.ms(bpm, ts))
     }
 
     /// Convert to the unit value of `Samples`.
     #[inline]
-    pub fn samples(&self, bpm: Bpm, ts: TimeSig, sample_hz: SampleHz) -> calc::Samples {
+    pub fn samples(&self, bpm: impl Into<Bpm>, ts: TimeSig, sample_hz: SampleHz) -> calc::Samples {
         samples_from_measure(self.bars(), Division::Bar, DivType::Whole, bpm, ts, sample_hz)
     }
     /// Convert to `Samples`.
     #[inline]
-    pub fn to_samples(&self, bpm: Bpm, ts: TimeSig, sample_hz: SampleHz) -> Samples {
+    pub fn to_samples(&self, bpm: impl Into<Bpm>, ts: TimeSig, sample_hz: SampleHz) -> Samples {
         Samples(self.samples(bpm, ts, sample_hz))
     }
 
@@ -78,6 +79,44 @@ impl Bars {
         Ticks(self.ticks(ts, ppqn))
     }
 
+    /// Checked addition. Returns `None` if overflow occurred.
+    #[inline]
+    pub fn checked_add(&self, rhs: Self) -> Option<Self> {
+        self.bars().checked_add(rhs.bars()).map(Self)
+    }
+    /// Checked subtraction. Returns `None` if overflow occurred.
+    #[inline]
+    pub fn checked_sub(&self, rhs: Self) -> Option<Self> {
+        self.bars().checked_sub(rhs.bars()).map(Self)
+    }
+    /// Checked multiplication by a scalar. Returns `None` if overflow occurred.
+    #[inline]
+    pub fn checked_mul(&self, rhs: NumDiv) -> Option<Self> {
+        self.bars().checked_mul(rhs).map(Self)
+    }
+    /// Checked division by a scalar. Returns `None` if `rhs == 0` or overflow occurred.
+    #[inline]
+    pub fn checked_div(&self, rhs: NumDiv) -> Option<Self> {
+        self.bars().checked_div(rhs).map(Self)
+    }
+
+    /// Saturating addition. Clamps the result at the numeric bounds instead of overflowing.
+    #[inline]
+    pub fn saturating_add(&self, rhs: Self) -> Self {
+        Self(self.bars().saturating_add(rhs.bars()))
+    }
+    /// Saturating subtraction. Clamps the result at the numeric bounds instead of overflowing.
+    #[inline]
+    pub fn saturating_sub(&self, rhs: Self) -> Self {
+        Self(self.bars().saturating_sub(rhs.bars()))
+    }
+    /// Saturating multiplication by a scalar. Clamps the result at the numeric bounds instead of
+    /// overflowing.
+    #[inline]
+    pub fn saturating_mul(&self, rhs: NumDiv) -> Self {
+        Self(self.bars().saturating_mul(rhs))
+    }
+
 }
 
 impl From<NumDiv> for Bars {
@@ -102,19 +141,35 @@ impl Sub for Bars {
     }
 }
 
-impl Mul for Bars {
+impl Mul<NumDiv> for Bars {
     type Output = Self;
     #[inline]
-    fn mul(self, rhs: Self) -> Self {
-        Self(self.bars() * rhs.bars())
+    fn mul(self, rhs: NumDiv) -> Self {
+        Self(self.bars() * rhs)
     }
 }
 
-impl Div for Bars {
+impl Mul<Bars> for NumDiv {
+    type Output = Bars;
+    #[inline]
+    fn mul(self, rhs: Bars) -> Bars {
+        Bars(self * rhs.bars())
+    }
+}
+
+impl Div<NumDiv> for Bars {
     type Output = Self;
     #[inline]
-    fn div(self, rhs: Self) -> Self {
-        Self(self.bars() / rhs.bars())
+    fn div(self, rhs: NumDiv) -> Self {
+        Self(self.bars() / rhs)
+    }
+}
+
+impl Div for Bars {
+    type Output = f64;
+    #[inline]
+    fn div(self, rhs: Self) -> f64 {
+        self.bars() as f64 / rhs.bars() as f64
     }
 }
 
@@ -146,14 +201,14 @@ impl SubAssign for Bars {
     }
 }
 
-impl MulAssign for Bars {
-    fn mul_assign(&mut self, rhs: Self) {
+impl MulAssign<NumDiv> for Bars {
+    fn mul_assign(&mut self, rhs: NumDiv) {
         *self = *self * rhs;
     }
 }
 
-impl DivAssign for Bars {
-    fn div_assign(&mut self, rhs: Self) {
+impl DivAssign<NumDiv> for Bars {
+    fn div_assign(&mut self, rhs: NumDiv) {
         *self = *self / rhs;
     }
 }
@@ -164,6 +219,18 @@ impl RemAssign for Bars {
     }
 }
 
+impl Sum for Bars {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Bars(0), |total, x| total + x)
+    }
+}
+
+impl<'a> Sum<&'a Bars> for Bars {
+    fn sum<I: Iterator<Item = &'a Bars>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
 impl ToPrimitive for Bars {
     fn to_u64(&self) -> Option<u64> {
         self.bars().to_u64()
@@ -0,0 +1,140 @@
+//! The beat grid of an audio clip, as used for beat-matching.
+
+use crate::calc;
+use crate::{
+    Bars,
+    Beats,
+    Bpm,
+    NumDiv,
+    SampleHz,
+    Samples,
+    TempoCandidate,
+    TimeSig,
+    MINUTE_IN_MS,
+    SECOND_IN_MS,
+};
+
+/// The beat grid of an audio clip: the position of its first downbeat, its tempo and optionally
+/// its time signature.
+///
+/// Beats and bars are counted from the first downbeat, so positions before it are negative.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BeatGrid {
+    /// The position of the first downbeat within the clip.
+    pub downbeat: Samples,
+    pub bpm: Bpm,
+    /// Required to convert to and from `Bars`.
+    pub ts: Option<TimeSig>,
+    /// The sample rate of the clip.
+    pub sample_hz: SampleHz,
+}
+
+/// The playback rate and position at which a clip must be played for its beat grid to line up
+/// with another.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BeatMatch {
+    /// The number of the clip's samples to advance for each sample of the target.
+    ///
+    /// This accounts for both the difference in tempo and the difference in sample rate.
+    pub rate: f64,
+    /// The position within the target at which the clip's `Samples(0)` must be placed for the
+    /// clip's first downbeat to land on the target's first downbeat.
+    pub offset: Samples,
+}
+
+impl BeatGrid {
+
+    /// Construct a beat grid without a time signature.
+    #[inline]
+    pub fn new(downbeat: Samples, bpm: impl Into<Bpm>, sample_hz: SampleHz) -> BeatGrid {
+        BeatGrid { downbeat, bpm: bpm.into(), ts: None, sample_hz }
+    }
+
+    /// The duration of a beat in samples.
+    #[inline]
+    pub fn samples_per_beat(&self) -> f64 {
+        MINUTE_IN_MS / self.bpm * self.sample_hz / SECOND_IN_MS
+    }
+
+    /// The fractional number of beats from the first downbeat to the given position.
+    #[inline]
+    pub fn beats_at(&self, samples: Samples) -> f64 {
+        (samples - self.downbeat).samples() as f64 / self.samples_per_beat()
+    }
+    /// The position of the given fractional number of beats from the first downbeat.
+    #[inline]
+    pub fn samples_at_beats(&self, beats: f64) -> Samples {
+        let offset = (beats * self.samples_per_beat()).round() as calc::Samples;
+        self.downbeat + Samples(offset)
+    }
+
+    /// The fractional number of bars from the first downbeat to the given position.
+    ///
+    /// Returns `None` if the grid has no time signature.
+    #[inline]
+    pub fn bars_at(&self, samples: Samples) -> Option<f64> {
+        self.ts.map(|ts| self.beats_at(samples) / ts.beats_per_bar())
+    }
+    /// The position of the given fractional number of bars from the first downbeat.
+    ///
+    /// Returns `None` if the grid has no time signature.
+    #[inline]
+    pub fn samples_at_bars(&self, bars: f64) -> Option<Samples> {
+        self.ts.map(|ts| self.samples_at_beats(bars * ts.beats_per_bar()))
+    }
+
+    /// Convert a position to the beat on which it falls.
+    #[inline]
+    pub fn to_beats(&self, samples: Samples) -> Beats {
+        Beats(self.beats_at(samples).floor() as NumDiv)
+    }
+    /// Convert a beat to its position.
+    #[inline]
+    pub fn beat_to_samples(&self, beats: Beats) -> Samples {
+        self.samples_at_beats(beats.beats() as f64)
+    }
+
+    /// Convert a position to the bar in which it falls.
+    ///
+    /// Returns `None` if the grid has no time signature.
+    #[inline]
+    pub fn to_bars(&self, samples: Samples) -> Option<Bars> {
+        self.bars_at(samples).map(|bars| Bars(bars.floor() as NumDiv))
+    }
+    /// Convert a bar to the position of its downbeat.
+    ///
+    /// Returns `None` if the grid has no time signature.
+    #[inline]
+    pub fn bar_to_samples(&self, bars: Bars) -> Option<Samples> {
+        self.samples_at_bars(bars.bars() as f64)
+    }
+
+    /// The beat nearest to the given position along with its position.
+    #[inline]
+    pub fn nearest_beat(&self, samples: Samples) -> (Beats, Samples) {
+        let beats = Beats(self.beats_at(samples).round() as NumDiv);
+        (beats, self.beat_to_samples(beats))
+    }
+
+    /// The rate and offset required for this grid to line up with the `target` grid.
+    pub fn beat_match(&self, target: &BeatGrid) -> BeatMatch {
+        let rate = (target.bpm / self.bpm) * (self.sample_hz / target.sample_hz);
+        let downbeat_in_target = (self.downbeat.samples() as f64 / rate).round() as calc::Samples;
+        BeatMatch { rate, offset: target.downbeat - Samples(downbeat_in_target) }
+    }
+
+    /// The rate and offset required for this grid to line up with a project at the given tempo
+    /// and sample rate whose first downbeat is at `Samples(0)`.
+    #[inline]
+    pub fn beat_match_tempo(&self, bpm: impl Into<Bpm>, sample_hz: SampleHz) -> BeatMatch {
+        self.beat_match(&BeatGrid::new(Samples(0), bpm, sample_hz))
+    }
+
+}
+
+impl From<TempoCandidate> for BeatGrid {
+    fn from(candidate: TempoCandidate) -> Self {
+        BeatGrid::new(candidate.downbeat, candidate.bpm, candidate.sample_hz)
+    }
+}
@@ -6,6 +6,7 @@
 //!
 
 use num::{FromPrimitive, ToPrimitive};
+use std::iter::Sum;
 use std::ops::{Add, Sub, Mul, Div, Rem, Neg,
                AddAssign, SubAssign, MulAssign, DivAssign, RemAssign};
 use super::calc;
@@ -46,23 +47,23 @@ impl Beats {
 
     /// Convert to the unit value of `Ms`.
     #[inline]
-    pub fn ms(&self, bpm: Bpm) -> calc::Ms {
+    pub fn ms(&self, bpm: impl Into<Bpm>) -> calc::Ms {
         self.beats() as calc::Ms * beat_in_ms(bpm)
     }
     /// Convert to `Ms`.
     #[inline]
-    pub fn to_ms(&self, bpm: Bpm) -> Ms {
+    pub fn to_ms(&self, bpm: impl Into<Bpm>) -> Ms {
         Ms(self.ms(bpm))
     }
 
     /// Convert to the unit value of `Samples`.
     #[inline]
-    pub fn samples(&self, bpm: Bpm, sample_hz: SampleHz) -> calc::Samples {
+    pub fn samples(&self, bpm: impl Into<Bpm>, sample_hz: SampleHz) -> calc::Samples {
         samples_from_ms(self.ms(bpm), sample_hz)
     }
     /// Conver to `Samples.
     #[inline]
-    pub fn to_samples(&self, bpm: Bpm, sample_hz: SampleHz) -> Samples {
+    pub fn to_samples(&self, bpm: impl Into<Bpm>, sample_hz: SampleHz) -> Samples {
         Samples(self.samples(bpm, sample_hz))
     }
 
@@ -78,6 +79,44 @@ impl Beats {
         Ticks(self.ticks(ppqn))
     }
 
+    /// Checked addition. Returns `None` if overflow occurred.
+    #[inline]
+    pub fn checked_add(&self, rhs: Self) -> Option<Self> {
+        self.beats().checked_add(rhs.beats()).map(Self)
+    }
+    /// Checked subtraction. Returns `None` if overflow occurred.
+    #[inline]
+    pub fn checked_sub(&self, rhs: Self) -> Option<Self> {
+        self.beats().checked_sub(rhs.beats()).map(Self)
+    }
+    /// Checked multiplication by a scalar. Returns `None` if overflow occurred.
+    #[inline]
+    pub fn checked_mul(&self, rhs: NumDiv) -> Option<Self> {
+        self.beats().checked_mul(rhs).map(Self)
+    }
+    /// Checked division by a scalar. Returns `None` if `rhs == 0` or overflow occurred.
+    #[inline]
+    pub fn checked_div(&self, rhs: NumDiv) -> Option<Self> {
+        self.beats().checked_div(rhs).map(Self)
+    }
+
+    /// Saturating addition. Clamps the result at the numeric bounds instead of overflowing.
+    #[inline]
+    pub fn saturating_add(&self, rhs: Self) -> Self {
+        Self(self.beats().saturating_add(rhs.beats()))
+    }
+    /// Saturating subtraction. Clamps the result at the numeric bounds instead of overflowing.
+    #[inline]
+    pub fn saturating_sub(&self, rhs: Self) -> Self {
+        Self(self.beats().saturating_sub(rhs.beats()))
+    }
+    /// Saturating multiplication by a scalar. Clamps the result at the numeric bounds instead of
+    /// overflowing.
+    #[inline]
+    pub fn saturating_mul(&self, rhs: NumDiv) -> Self {
+        Self(self.beats().saturating_mul(rhs))
+    }
+
 }
 
 impl From<NumDiv> for Beats {
@@ -102,19 +141,35 @@ impl Sub for Beats {
     }
 }
 
-impl Mul for Beats {
+impl Mul<NumDiv> for Beats {
     type Output = Self;
     #[inline]
-    fn mul(self, rhs: Self) -> Self {
-        Self(self.beats() * rhs.beats())
+    fn mul(self, rhs: NumDiv) -> Self {
+        Self(self.beats() * rhs)
     }
 }
 
-impl Div for Beats {
+impl Mul<Beats> for NumDiv {
+    type Output = Beats;
+    #[inline]
+    fn mul(self, rhs: Beats) -> Beats {
+        Beats(self * rhs.beats())
+    }
+}
+
+impl Div<NumDiv> for Beats {
     type Output = Self;
     #[inline]
-    fn div(self, rhs: Self) -> Self {
-        Self(self.beats() / rhs.beats())
+    fn div(self, rhs: NumDiv) -> Self {
+        Self(self.beats() / rhs)
+    }
+}
+
+impl Div for Beats {
+    type Output = f64;
+    #[inline]
+    fn div(self, rhs: Self) -> f64 {
+        self.beats() as f64 / rhs.beats() as f64
     }
 }
 
@@ -146,14 +201,14 @@ impl SubAssign for Beats {
     }
 }
 
-impl MulAssign for Beats {
-    fn mul_assign(&mut self, rhs: Self) {
+impl MulAssign<NumDiv> for Beats {
+    fn mul_assign(&mut self, rhs: NumDiv) {
         *self = *self * rhs;
     }
 }
 
-impl DivAssign for Beats {
-    fn div_assign(&mut self, rhs: Self) {
+impl DivAssign<NumDiv> for Beats {
+    fn div_assign(&mut self, rhs: NumDiv) {
         *self = *self / rhs;
     }
 }
@@ -164,6 +219,18 @@ impl RemAssign for Beats {
     }
 }
 
+impl Sum for Beats {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Beats(0), |total, x| total + x)
+    }
+}
+
+impl<'a> Sum<&'a Beats> for Beats {
+    fn sum<I: Iterator<Item = &'a Beats>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
 impl ToPrimitive for Beats {
     fn to_u64(&self) -> Option<u64> {
         self.beats().to_u64()
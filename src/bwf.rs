@@ -0,0 +1,323 @@
+//! The Broadcast Wave Format `bext` TimeReference and time-of-day timecode.
+
+use std::error::Error;
+use std::fmt;
+use crate::calc;
+use crate::{
+    FrameRate,
+    Frames,
+    Rounding,
+    SampleHz,
+    Samples,
+};
+
+/// The offset of the TimeReference field within the data of a `bext` chunk.
+const BEXT_TIME_REFERENCE_OFFSET: usize = 338;
+
+/// The position of a recording as the number of samples since midnight, as stored within the
+/// `bext` chunk of a Broadcast Wave file.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TimeReference(pub u64);
+
+/// A time of day in the form `hh:mm:ss:ff`.
+///
+/// Frames are labelled using the frame rate's `nominal_fps`. When `drop_frame` is set, frame
+/// labels are skipped at the start of each minute except every tenth (two labels at 30 frames,
+/// four at 60) so that the timecode keeps pace with the clock at 29.97 and 59.94. Drop-frame is
+/// only defined at those two rates, so `drop_frame` is ignored at any other.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Timecode {
+    pub hours: u8,
+    pub minutes: u8,
+    pub seconds: u8,
+    pub frames: u8,
+    pub drop_frame: bool,
+}
+
+/// The reasons the TimeReference of a WAV buffer may not be read or written.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum BwfError {
+    /// The buffer does not begin with a RIFF/WAVE header.
+    NotWave,
+    /// The buffer contains no `bext` chunk.
+    NoBextChunk,
+    /// The `bext` chunk is too short to contain a TimeReference.
+    Truncated,
+}
+
+impl fmt::Display for BwfError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match *self {
+            BwfError::NotWave => "expected a RIFF/WAVE header",
+            BwfError::NoBextChunk => "no `bext` chunk",
+            BwfError::Truncated => "the `bext` chunk is too short to contain a time reference",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl Error for BwfError {}
+
+impl TimeReference {
+
+    /// Return the number of samples since midnight.
+    #[inline]
+    pub fn samples(&self) -> u64 { let TimeReference(samples) = *self; samples }
+
+    /// Construct from a time of day.
+    #[inline]
+    pub fn from_timecode(timecode: Timecode, rate: FrameRate, sample_hz: SampleHz) -> TimeReference {
+        let samples = timecode.to_frames(rate).to_samples_rounded(rate, sample_hz, Rounding::Nearest);
+        TimeReference(samples.samples().max(0) as u64)
+    }
+
+    /// Convert to a time of day, rounding to a frame.
+    #[inline]
+    pub fn to_timecode(&self, rate: FrameRate, sample_hz: SampleHz, drop_frame: bool,
+                       rounding: Rounding) -> Timecode {
+        let samples = Samples(self.samples().min(calc::Samples::MAX as u64) as calc::Samples);
+        let frames = Frames::from_samples(samples, rate, sample_hz, rounding);
+        Timecode::from_frames(frames, rate, drop_frame)
+    }
+
+    /// The position relative to a project whose `Samples(0)` lies at `origin`.
+    ///
+    /// Both time references must share the project's sample rate.
+    #[inline]
+    pub fn to_samples(&self, origin: TimeReference) -> Samples {
+        let offset = self.samples() as i128 - origin.samples() as i128;
+        Samples(offset.clamp(calc::Samples::MIN as i128, calc::Samples::MAX as i128) as calc::Samples)
+    }
+
+    /// Construct from a position within a project whose `Samples(0)` lies at `origin`.
+    ///
+    /// Positions before midnight saturate to `TimeReference(0)`.
+    #[inline]
+    pub fn from_samples(samples: Samples, origin: TimeReference) -> TimeReference {
+        let samples = origin.samples() as i128 + samples.samples() as i128;
+        TimeReference(samples.clamp(0, u64::MAX as i128) as u64)
+    }
+
+    /// Read the TimeReference from the `bext` chunk of a WAV file.
+    pub fn read_wav(bytes: &[u8]) -> Result<TimeReference, BwfError> {
+        let start = bext_time_reference_offset(bytes)?;
+        let mut field = [0; 8];
+        field.copy_from_slice(&bytes[start..start + 8]);
+        Ok(TimeReference(u64::from_le_bytes(field)))
+    }
+
+    /// Write the TimeReference to the `bext` chunk of a WAV file.
+    pub fn write_wav(&self, bytes: &mut [u8]) -> Result<(), BwfError> {
+        let start = bext_time_reference_offset(bytes)?;
+        bytes[start..start + 8].copy_from_slice(&self.samples().to_le_bytes());
+        Ok(())
+    }
+
+}
+
+impl Timecode {
+
+    /// Construct a non-drop-frame timecode.
+    #[inline]
+    pub fn new(hours: u8, minutes: u8, seconds: u8, frames: u8) -> Timecode {
+        Timecode { hours, minutes, seconds, frames, drop_frame: false }
+    }
+
+    /// Construct from the number of frames since midnight, wrapping at 24 hours.
+    ///
+    /// `drop_frame` is ignored unless `rate` is 29.97 or 59.94.
+    pub fn from_frames(frames: Frames, rate: FrameRate, drop_frame: bool) -> Timecode {
+        let drop_frame = drop_frame && is_drop_frame_rate(rate);
+        let fps = rate.nominal_fps() as calc::Frames;
+        let day = Timecode { drop_frame, ..Timecode::new(24, 0, 0, 0) }.to_frames(rate).frames();
+        let mut frames = frames.frames().rem_euclid(day);
+        if drop_frame {
+            // Skip over the dropped labels that precede the frame.
+            let dropped = fps / 15;
+            let per_minute = fps * 60 - dropped;
+            let per_ten_minutes = fps * 600 - dropped * 9;
+            let tens = frames / per_ten_minutes;
+            let rem = frames % per_ten_minutes;
+            let minutes = if rem > dropped { (rem - dropped) / per_minute } else { 0 };
+            frames += dropped * (9 * tens + minutes);
+        }
+        Timecode {
+            hours: (frames / (fps * 3_600)) as u8,
+            minutes: (frames / (fps * 60) % 60) as u8,
+            seconds: (frames / fps % 60) as u8,
+            frames: (frames % fps) as u8,
+            drop_frame,
+        }
+    }
+
+    /// Convert to the number of frames since midnight.
+    ///
+    /// `drop_frame` is ignored unless `rate` is 29.97 or 59.94.
+    pub fn to_frames(&self, rate: FrameRate) -> Frames {
+        let fps = rate.nominal_fps() as calc::Frames;
+        let minutes = self.hours as calc::Frames * 60 + self.minutes as calc::Frames;
+        let seconds = minutes * 60 + self.seconds as calc::Frames;
+        let frames = seconds * fps + self.frames as calc::Frames;
+        if self.drop_frame && is_drop_frame_rate(rate) {
+            Frames(frames - fps / 15 * (minutes - minutes / 10))
+        } else {
+            Frames(frames)
+        }
+    }
+
+}
+
+impl fmt::Display for Timecode {
+    /// Drop-frame timecode separates the frames with `;`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sep = if self.drop_frame { ';' } else { ':' };
+        write!(f, "{:02}:{:02}:{:02}{}{:02}", self.hours, self.minutes, self.seconds, sep, self.frames)
+    }
+}
+
+/// Whether or not drop-frame timecode is defined at the given rate, i.e. 29.97 or 59.94.
+fn is_drop_frame_rate(rate: FrameRate) -> bool {
+    let nominal = rate.nominal_fps() as u64;
+    let (num, den) = (rate.num.get() as u64, rate.den.get() as u64);
+    (nominal == 30 || nominal == 60) && num * 1_001 == nominal * 1_000 * den
+}
+
+/// The offset of the TimeReference field within a RIFF/WAVE buffer.
+fn bext_time_reference_offset(bytes: &[u8]) -> Result<usize, BwfError> {
+    let is_riff = bytes.len() >= 12
+        && (&bytes[0..4] == b"RIFF" || &bytes[0..4] == b"RF64")
+        && &bytes[8..12] == b"WAVE";
+    if !is_riff {
+        return Err(BwfError::NotWave);
+    }
+    let mut pos = 12;
+    while pos + 8 <= bytes.len() {
+        let id = &bytes[pos..pos + 4];
+        let mut size = [0; 4];
+        size.copy_from_slice(&bytes[pos + 4..pos + 8]);
+        let size = u32::from_le_bytes(size) as usize;
+        let data = pos + 8;
+        if id == b"bext" {
+            let field = data + BEXT_TIME_REFERENCE_OFFSET;
+            if size < BEXT_TIME_REFERENCE_OFFSET + 8 || field + 8 > bytes.len() {
+                return Err(BwfError::Truncated);
+            }
+            return Ok(field);
+        }
+        // Chunks are padded to an even length.
+        pos = data.saturating_add(size).saturating_add(size & 1);
+    }
+    Err(BwfError::NoBextChunk)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A RIFF/WAVE buffer containing the given chunks, padded to even lengths.
+    fn wav(chunks: &[(&[u8; 4], Vec<u8>)]) -> Vec<u8> {
+        let mut body = b"WAVE".to_vec();
+        for (id, data) in chunks {
+            body.extend_from_slice(*id);
+            body.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            body.extend_from_slice(data);
+            if data.len() % 2 == 1 {
+                body.push(0);
+            }
+        }
+        let mut bytes = b"RIFF".to_vec();
+        bytes.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        bytes.extend(body);
+        bytes
+    }
+
+    fn bext() -> Vec<u8> {
+        vec![0; 602]
+    }
+
+    #[test]
+    fn drop_frame_round_trip_over_a_day() {
+        for rate in [FrameRate::FPS_29_97, FrameRate::FPS_59_94] {
+            let fps = rate.nominal_fps() as u8;
+            let day = Timecode { drop_frame: true, ..Timecode::new(24, 0, 0, 0) }.to_frames(rate);
+            for frames in 0..day.frames() {
+                let timecode = Timecode::from_frames(Frames(frames), rate, true);
+                assert_eq!(timecode.to_frames(rate), Frames(frames), "{}", timecode);
+                assert!(timecode.frames < fps);
+                if timecode.seconds == 0 && !timecode.minutes.is_multiple_of(10) {
+                    assert!(timecode.frames >= fps / 15, "{}", timecode);
+                }
+            }
+            let midnight = Timecode { drop_frame: true, ..Timecode::new(0, 0, 0, 0) };
+            assert_eq!(Timecode::from_frames(day, rate, true), midnight);
+        }
+    }
+
+    #[test]
+    fn drop_frame_labels() {
+        let rate = FrameRate::FPS_29_97;
+        let before = Timecode { drop_frame: true, ..Timecode::new(0, 0, 59, 29) };
+        let after = Timecode::from_frames(before.to_frames(rate) + Frames(1), rate, true);
+        assert_eq!(after, Timecode { drop_frame: true, ..Timecode::new(0, 1, 0, 2) });
+        assert_eq!(after.to_string(), "00:01:00;02");
+        let tenth = Timecode { drop_frame: true, ..Timecode::new(0, 10, 0, 0) };
+        assert_eq!(Timecode::from_frames(tenth.to_frames(rate), rate, true), tenth);
+    }
+
+    #[test]
+    fn drop_frame_ignored_at_other_rates() {
+        for rate in [FrameRate::FPS_24, FrameRate::FPS_25, FrameRate::FPS_30, FrameRate::FPS_23_976] {
+            let fps = rate.nominal_fps() as calc::Frames;
+            let timecode = Timecode::from_frames(Frames(fps * 60 + 1), rate, true);
+            assert_eq!(timecode, Timecode::new(0, 1, 0, 1));
+            assert_eq!(timecode.to_string(), "00:01:00:01");
+            let flagged = Timecode { drop_frame: true, ..Timecode::new(0, 1, 0, 1) };
+            assert_eq!(flagged.to_frames(rate), Frames(fps * 60 + 1));
+        }
+        let rate = FrameRate::new(60_000, 2_002).unwrap();
+        let timecode = Timecode::from_frames(Frames(30 * 60), rate, true);
+        assert_eq!(timecode, Timecode { drop_frame: true, ..Timecode::new(0, 1, 0, 2) });
+    }
+
+    #[test]
+    fn read_write_minimal_wav() {
+        let mut bytes = wav(&[(b"bext", bext()), (b"data", vec![0; 4])]);
+        assert_eq!(TimeReference::read_wav(&bytes), Ok(TimeReference(0)));
+        let reference = TimeReference(48_000 * 3_600);
+        reference.write_wav(&mut bytes).unwrap();
+        assert_eq!(TimeReference::read_wav(&bytes), Ok(reference));
+        assert_eq!(&bytes[20 + BEXT_TIME_REFERENCE_OFFSET..28 + BEXT_TIME_REFERENCE_OFFSET],
+                   &reference.samples().to_le_bytes());
+    }
+
+    #[test]
+    fn odd_sized_chunk_before_bext() {
+        let mut bytes = wav(&[(b"junk", vec![0xFF; 3]), (b"bext", bext())]);
+        let reference = TimeReference(1_234_567);
+        reference.write_wav(&mut bytes).unwrap();
+        assert_eq!(TimeReference::read_wav(&bytes), Ok(reference));
+    }
+
+    #[test]
+    fn wav_errors() {
+        assert_eq!(TimeReference::read_wav(b"RIFF\0\0\0\0AVI "), Err(BwfError::NotWave));
+        assert_eq!(TimeReference::read_wav(b"RIFF"), Err(BwfError::NotWave));
+        assert_eq!(TimeReference::read_wav(&wav(&[(b"data", vec![0; 4])])), Err(BwfError::NoBextChunk));
+        assert_eq!(TimeReference::read_wav(&wav(&[(b"bext", vec![0; 100])])), Err(BwfError::Truncated));
+        let mut bytes = wav(&[(b"bext", bext())]);
+        bytes.truncate(12 + 8 + BEXT_TIME_REFERENCE_OFFSET + 4);
+        assert_eq!(TimeReference::read_wav(&bytes), Err(BwfError::Truncated));
+        assert_eq!(TimeReference(1).write_wav(&mut bytes), Err(BwfError::Truncated));
+    }
+
+    #[test]
+    fn time_reference_timecode_round_trip() {
+        let timecode = Timecode::new(10, 0, 0, 0);
+        let reference = TimeReference::from_timecode(timecode, FrameRate::FPS_25, 48_000.0);
+        assert_eq!(reference, TimeReference(48_000 * 36_000));
+        let back = reference.to_timecode(FrameRate::FPS_25, 48_000.0, false, Rounding::Nearest);
+        assert_eq!(back, timecode);
+    }
+}
@@ -15,31 +15,137 @@ use super::division::{
     DivType,
 };
 
+/// Tempo in quarter notes per minute. Conversions accepting `impl Into<Bpm>` also accept a `Tempo`.
 pub type Bpm = f64;
+pub type Frames = i64;
 pub type Ppqn = u32;
 pub type Ms = f64;
+pub type Nanos = i64;
 pub type SampleHz = f64;
 pub type Samples = i64;
+pub type Seconds = f64;
 pub type Ticks = i64;
 
 pub const MINUTE_IN_MS: Ms = 60_000.0;
 pub const SECOND_IN_MS: Ms =  1_000.0;
+pub const SECOND_IN_NS: Nanos = 1_000_000_000;
+pub const MS_IN_NS: Nanos = 1_000_000;
+
+/// The rounding mode used by conversions that must produce a whole number of units.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Rounding {
+    /// Round towards negative infinity.
+    Floor,
+    /// Round towards positive infinity.
+    Ceil,
+    /// Round to the nearest whole number, with halfway cases rounded away from zero.
+    Nearest,
+    /// Round towards zero.
+    Truncate,
+}
+
+impl Rounding {
+
+    /// Round the given value to a whole number.
+    #[inline]
+    pub fn round(&self, value: f64) -> f64 {
+        match *self {
+            Rounding::Floor => value.floor(),
+            Rounding::Ceil => value.ceil(),
+            Rounding::Nearest => value.round(),
+            Rounding::Truncate => value.trunc(),
+        }
+    }
+
+    /// Divide `num` by `den`, rounding the quotient to a whole number.
+    #[inline]
+    pub(crate) fn div(&self, num: i128, den: i128) -> i128 {
+        let (num, den) = if den < 0 { (-num, -den) } else { (num, den) };
+        let floor = num.div_euclid(den);
+        let rem = num.rem_euclid(den);
+        if rem == 0 {
+            return floor;
+        }
+        match *self {
+            Rounding::Floor => floor,
+            Rounding::Ceil => floor + 1,
+            Rounding::Truncate => if num < 0 { floor + 1 } else { floor },
+            Rounding::Nearest => match (2 * rem).cmp(&den) {
+                std::cmp::Ordering::Less => floor,
+                std::cmp::Ordering::Greater => floor + 1,
+                std::cmp::Ordering::Equal => if num < 0 { floor } else { floor + 1 },
+            },
+        }
+    }
+
+}
+
+/// The ratio between two rates (i.e. sample rates, or ticks per second), reduced once so that it
+/// may be applied to many values.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) enum RateRatio {
+    /// Both rates are whole numbers, so the ratio is `num / den` exactly.
+    Exact(i128, i128),
+    /// At least one of the rates is fractional.
+    Float(f64),
+}
+
+impl RateRatio {
+
+    /// The ratio of `to_hz` over `from_hz`.
+    pub(crate) fn new(from_hz: f64, to_hz: f64) -> RateRatio {
+        // The largest whole number that `f64` can represent exactly.
+        const MAX_EXACT: f64 = (1u64 << 53) as f64;
+        fn whole(hz: f64) -> Option<i128> {
+            if hz > 0.0 && hz.fract() == 0.0 && hz <= MAX_EXACT {
+                Some(hz as i128)
+            } else {
+                None
+            }
+        }
+        match (whole(from_hz), whole(to_hz)) {
+            (Some(from), Some(to)) => {
+                let gcd = num::integer::gcd(from, to);
+                RateRatio::Exact(to / gcd, from / gcd)
+            },
+            _ => RateRatio::Float(to_hz / from_hz),
+        }
+    }
+
+    /// Apply the ratio to the given value.
+    #[inline]
+    pub(crate) fn apply_i128(&self, value: i128, rounding: Rounding) -> i128 {
+        match *self {
+            RateRatio::Exact(num, den) => rounding.div(value.saturating_mul(num), den),
+            RateRatio::Float(ratio) => rounding.round(value as f64 * ratio) as i128,
+        }
+    }
+
+    /// Apply the ratio to the given number of samples.
+    #[inline]
+    pub(crate) fn apply(&self, samples: Samples, rounding: Rounding) -> Samples {
+        let converted = self.apply_i128(samples as i128, rounding);
+        converted.clamp(Samples::MIN as i128, Samples::MAX as i128) as Samples
+    }
+
+}
 
 /// Calculate and return the duration of a bar in milliseconds.
 #[inline]
-pub fn bar_in_ms(bpm: Bpm, ts: TimeSig) -> Ms {
+pub fn bar_in_ms(bpm: impl Into<Bpm>, ts: TimeSig) -> Ms {
     beat_in_ms(bpm) * ts.beats_per_bar()
 }
 
 /// Calculate and return the duration of a beat in milliseconds.
 #[inline]
-pub fn beat_in_ms(bpm: Bpm) -> Ms {
-    MINUTE_IN_MS / bpm
+pub fn beat_in_ms(bpm: impl Into<Bpm>) -> Ms {
+    MINUTE_IN_MS / bpm.into()
 }
 
 /// Calculate and return milliseconds from a given musical division.
 #[inline]
-pub fn ms_from_measure(num: NumDiv, div: Division, div_type: DivType, bpm: Bpm, ts: TimeSig) -> Ms {
+pub fn ms_from_measure(num: NumDiv, div: Division, div_type: DivType, bpm: impl Into<Bpm>, ts: TimeSig) -> Ms {
     Measure(num, div, div_type).beats(ts) * beat_in_ms(bpm)
 }
 
@@ -51,14 +157,14 @@ pub fn ms_from_samples(samples: Samples, sample_hz: SampleHz) -> Ms {
 
 /// Calculate and return milliseconds from a given number of ticks.
 #[inline]
-pub fn ms_from_ticks(ticks: Ticks, bpm: Bpm, ppqn: Ppqn) -> Ms {
+pub fn ms_from_ticks(ticks: Ticks, bpm: impl Into<Bpm>, ppqn: Ppqn) -> Ms {
     tick_in_ms(bpm, ppqn) * ticks as Ms
 }
 
 /// Calculate and return samples from a given musical division.
 #[inline]
 pub fn samples_from_measure(num: NumDiv, div: Division, div_type: DivType,
-                            bpm: Bpm, ts: TimeSig, sample_hz: SampleHz) -> Samples {
+                            bpm: impl Into<Bpm>, ts: TimeSig, sample_hz: SampleHz) -> Samples {
     samples_from_ms(ms_from_measure(num, div, div_type, bpm, ts), sample_hz)
 }
 
@@ -68,15 +174,25 @@ pub fn samples_from_ms(ms: Ms, sample_hz: SampleHz) -> Samples {
     (ms * sample_hz as Ms / SECOND_IN_MS) as Samples
 }
 
+/// Convert a number of samples at `from_hz` to the equivalent number of samples at `to_hz`.
+///
+/// When both rates are whole numbers the conversion uses exact integer ratio math. Otherwise it
+/// falls back to floating point arithmetic.
+#[inline]
+pub fn samples_from_sample_hz(samples: Samples, from_hz: SampleHz, to_hz: SampleHz,
+                              rounding: Rounding) -> Samples {
+    RateRatio::new(from_hz, to_hz).apply(samples, rounding)
+}
+
 /// Calculate and return samples from a given number of ticks.
 #[inline]
-pub fn samples_from_ticks(ticks: Ticks, bpm: Bpm, ppqn: Ppqn, sample_hz: SampleHz) -> Samples {
+pub fn samples_from_ticks(ticks: Ticks, bpm: impl Into<Bpm>, ppqn: Ppqn, sample_hz: SampleHz) -> Samples {
     samples_from_ms(ms_from_ticks(ticks, bpm, ppqn), sample_hz)
 }
 
 /// Calculate and return the duration of a tick in milliseconds.
 #[inline]
-pub fn tick_in_ms(bpm: Bpm, ppqn: Ppqn) -> Ms {
+pub fn tick_in_ms(bpm: impl Into<Bpm>, ppqn: Ppqn) -> Ms {
     beat_in_ms(bpm) / ppqn as Ms
 }
 
@@ -84,21 +200,52 @@ pub fn tick_in_ms(bpm: Bpm, ppqn: Ppqn) -> Ms {
 #[inline]
 pub fn ticks_from_measure(num: NumDiv, div: Division, div_type: DivType,
                           ts: TimeSig, ppqn: Ppqn) -> Ticks {
-    let num_quarters = match div_type {
-        DivType::TwoThirds => div.beats(ts) * num as f64 * 2.0 / 3.0,
-        DivType::Whole => div.beats(ts) * num as f64
-    };
+    let num_quarters = div.beats(ts) * num as f64 * div_type.ratio();
     (num_quarters * ppqn as f64).round() as Ticks
 }
 
+/// Rescale a number of ticks at the `from` resolution to the `to` resolution.
+///
+/// Returns the rescaled ticks along with the remainder lost to rounding, expressed as a fraction
+/// of a tick at the `to` resolution (the exact value minus the returned ticks). Returns `None` if
+/// `from` is `0` or if the rescaled ticks overflow `Ticks`.
+#[inline]
+pub fn ticks_from_ppqn(ticks: Ticks, from: Ppqn, to: Ppqn, rounding: Rounding) -> Option<(Ticks, f64)> {
+    if from == 0 {
+        return None;
+    }
+    let num = ticks as i128 * to as i128;
+    let den = from as i128;
+    let rescaled = rounding.div(num, den);
+    let error = (num - rescaled * den) as f64 / den as f64;
+    Ticks::try_from(rescaled).ok().map(|ticks| (ticks, error))
+}
+
+/// The lowest common multiple of the given resolutions.
+///
+/// Ticks at any of the given resolutions can be rescaled to this resolution without loss.
+/// Returns `None` if `ppqns` is empty, contains `0` or if the result would overflow `Ppqn`.
+pub fn common_ppqn(ppqns: &[Ppqn]) -> Option<Ppqn> {
+    let (&first, rest) = ppqns.split_first()?;
+    if first == 0 {
+        return None;
+    }
+    rest.iter().try_fold(first, |lcm, &ppqn| {
+        if ppqn == 0 {
+            return None;
+        }
+        lcm.checked_mul(ppqn / num::integer::gcd(lcm, ppqn))
+    })
+}
+
 /// Calculate and return the number of ticks from a given number of milliseconds.
 #[inline]
-pub fn ticks_from_ms(ms: Ms, bpm: Bpm, ppqn: Ppqn) -> Ticks {
+pub fn ticks_from_ms(ms: Ms, bpm: impl Into<Bpm>, ppqn: Ppqn) -> Ticks {
     (ms / tick_in_ms(bpm, ppqn)).round() as Ticks
 }
 
 /// Calculate and return a number of ticks from a given number of samples.
 #[inline]
-pub fn ticks_from_samples(samples: Samples, bpm: Bpm, ppqn: Ppqn, sample_hz: SampleHz) -> Ticks {
+pub fn ticks_from_samples(samples: Samples, bpm: impl Into<Bpm>, ppqn: Ppqn, sample_hz: SampleHz) -> Ticks {
     ticks_from_ms(ms_from_samples(samples, sample_hz), bpm, ppqn)
 }
@@ -0,0 +1,180 @@
+//! A shared tempo, meter, resolution and sample rate for converting between any two time units.
+
+use crate::calc;
+use crate::{
+    Bars,
+    Beats,
+    Bpm,
+    Division,
+    DivType,
+    Measure,
+    Ms,
+    Nanos,
+    NumDiv,
+    Ppqn,
+    SampleHz,
+    Samples,
+    Seconds,
+    Ticks,
+    TimeSig,
+    MS_IN_NS,
+    bar_in_ms,
+    beat_in_ms,
+    tick_in_ms,
+};
+
+/// Bundles the tempo, time signature, resolution and sample rate required to convert between any
+/// pair of time units.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TimeContext {
+    pub bpm: Bpm,
+    pub ts: TimeSig,
+    pub ppqn: Ppqn,
+    pub sample_hz: SampleHz,
+}
+
+/// A unit of time that may be converted to and from any other unit within a `TimeContext`.
+///
+/// Conversions pass through the unit value of `Ms`, matching the free functions in `calc`.
+pub trait TimeUnit: Sized {
+    /// Convert to the unit value of `Ms` within the given context.
+    fn to_context_ms(&self, ctx: &TimeContext) -> calc::Ms;
+    /// Convert from the unit value of `Ms` within the given context.
+    fn from_context_ms(ms: calc::Ms, ctx: &TimeContext) -> Self;
+}
+
+impl TimeContext {
+
+    /// Construct a new `TimeContext`.
+    #[inline]
+    pub fn new(bpm: impl Into<Bpm>, ts: TimeSig, ppqn: Ppqn, sample_hz: SampleHz) -> TimeContext {
+        TimeContext { bpm: bpm.into(), ts, ppqn, sample_hz }
+    }
+
+    /// Convert the given value to the time unit `U`.
+    ///
+    /// i.e. `ctx.convert::<Ticks>(Bars(2))`.
+    #[inline]
+    pub fn convert<U: TimeUnit>(&self, value: impl TimeUnit) -> U {
+        U::from_context_ms(value.to_context_ms(self), self)
+    }
+
+    /// The duration of a bar in milliseconds.
+    #[inline]
+    pub fn bar_in_ms(&self) -> calc::Ms {
+        bar_in_ms(self.bpm, self.ts)
+    }
+
+    /// The duration of a beat in milliseconds.
+    #[inline]
+    pub fn beat_in_ms(&self) -> calc::Ms {
+        beat_in_ms(self.bpm)
+    }
+
+    /// The duration of a tick in milliseconds.
+    #[inline]
+    pub fn tick_in_ms(&self) -> calc::Ms {
+        tick_in_ms(self.bpm, self.ppqn)
+    }
+
+}
+
+impl TimeUnit for Bars {
+    #[inline]
+    fn to_context_ms(&self, ctx: &TimeContext) -> calc::Ms {
+        self.ms(ctx.bpm, ctx.ts)
+    }
+    #[inline]
+    fn from_context_ms(ms: calc::Ms, ctx: &TimeContext) -> Self {
+        Bars(Ms(ms).bars(ctx.bpm, ctx.ts).round() as NumDiv)
+    }
+}
+
+impl TimeUnit for Beats {
+    #[inline]
+    fn to_context_ms(&self, ctx: &TimeContext) -> calc::Ms {
+        self.ms(ctx.bpm)
+    }
+    #[inline]
+    fn from_context_ms(ms: calc::Ms, ctx: &TimeContext) -> Self {
+        Beats(Ms(ms).beats(ctx.bpm).round() as NumDiv)
+    }
+}
+
+impl TimeUnit for Measure {
+    #[inline]
+    fn to_context_ms(&self, ctx: &TimeContext) -> calc::Ms {
+        self.ms(ctx.bpm, ctx.ts)
+    }
+    /// Produces the `Measure` with the coarsest `Division` that represents the duration exactly,
+    /// falling back to the nearest number of the finest `Division`.
+    fn from_context_ms(ms: calc::Ms, ctx: &TimeContext) -> Self {
+        const EPSILON: f64 = 1e-9;
+        let beats = Ms(ms).beats(ctx.bpm);
+        let mut div = Division::Bar;
+        loop {
+            let num = beats / div.beats(ctx.ts);
+            let next = div.zoom_in(1);
+            if (num - num.round()).abs() < EPSILON || next.is_none() {
+                return Measure(num.round() as NumDiv, div, DivType::Whole);
+            }
+            div = next.unwrap();
+        }
+    }
+}
+
+impl TimeUnit for Ms {
+    #[inline]
+    fn to_context_ms(&self, _ctx: &TimeContext) -> calc::Ms {
+        self.ms()
+    }
+    #[inline]
+    fn from_context_ms(ms: calc::Ms, _ctx: &TimeContext) -> Self {
+        Ms(ms)
+    }
+}
+
+impl TimeUnit for Samples {
+    #[inline]
+    fn to_context_ms(&self, ctx: &TimeContext) -> calc::Ms {
+        self.ms(ctx.sample_hz)
+    }
+    #[inline]
+    fn from_context_ms(ms: calc::Ms, ctx: &TimeContext) -> Self {
+        Ms(ms).to_samples(ctx.sample_hz)
+    }
+}
+
+impl TimeUnit for Ticks {
+    #[inline]
+    fn to_context_ms(&self, ctx: &TimeContext) -> calc::Ms {
+        self.ms(ctx.bpm, ctx.ppqn)
+    }
+    #[inline]
+    fn from_context_ms(ms: calc::Ms, ctx: &TimeContext) -> Self {
+        Ms(ms).to_ticks(ctx.bpm, ctx.ppqn)
+    }
+}
+
+impl TimeUnit for Seconds {
+    #[inline]
+    fn to_context_ms(&self, _ctx: &TimeContext) -> calc::Ms {
+        self.ms()
+    }
+    #[inline]
+    fn from_context_ms(ms: calc::Ms, _ctx: &TimeContext) -> Self {
+        Seconds::from(Ms(ms))
+    }
+}
+
+impl TimeUnit for Nanos {
+    #[inline]
+    fn to_context_ms(&self, _ctx: &TimeContext) -> calc::Ms {
+        self.ms()
+    }
+    #[inline]
+    fn from_context_ms(ms: calc::Ms, _ctx: &TimeContext) -> Self {
+        Nanos((ms * MS_IN_NS as calc::Ms).round() as calc::Nanos)
+    }
+}
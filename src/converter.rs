@@ -0,0 +1,161 @@
+//! Precomputed conversions between Ticks, Samples and Ms for a fixed tempo and sample rate.
+
+use crate::calc;
+use crate::{
+    Bpm,
+    Ms,
+    Ppqn,
+    SampleHz,
+    Samples,
+    Ticks,
+    TimeContext,
+    SECOND_IN_MS,
+    tick_in_ms,
+};
+
+/// Converts between `Ticks`, `Samples` and `Ms` using factors precomputed for a fixed tempo,
+/// resolution and sample rate.
+///
+/// Each conversion is a single multiplication, making the `Converter` suitable for converting
+/// every event within every audio block. Results use the same rounding as the free functions in
+/// `calc`, though they may differ by one unit where floating point error lands on a boundary.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Converter {
+    samples_per_tick: f64,
+    ticks_per_sample: f64,
+    samples_per_ms: f64,
+    ms_per_sample: f64,
+    ticks_per_ms: f64,
+    ms_per_tick: f64,
+}
+
+impl Converter {
+
+    /// Precompute the conversion factors for the given tempo, resolution and sample rate.
+    pub fn new(bpm: impl Into<Bpm>, ppqn: Ppqn, sample_hz: SampleHz) -> Converter {
+        let ms_per_tick = tick_in_ms(bpm, ppqn);
+        let samples_per_ms = sample_hz / SECOND_IN_MS;
+        let samples_per_tick = ms_per_tick * samples_per_ms;
+        Converter {
+            samples_per_tick,
+            ticks_per_sample: 1.0 / samples_per_tick,
+            samples_per_ms,
+            ms_per_sample: 1.0 / samples_per_ms,
+            ticks_per_ms: 1.0 / ms_per_tick,
+            ms_per_tick,
+        }
+    }
+
+    /// The number of samples in a single tick.
+    #[inline]
+    pub fn samples_per_tick(&self) -> f64 { self.samples_per_tick }
+    /// The number of ticks in a single sample.
+    #[inline]
+    pub fn ticks_per_sample(&self) -> f64 { self.ticks_per_sample }
+    /// The number of samples in a single millisecond.
+    #[inline]
+    pub fn samples_per_ms(&self) -> f64 { self.samples_per_ms }
+    /// The number of milliseconds in a single sample.
+    #[inline]
+    pub fn ms_per_sample(&self) -> f64 { self.ms_per_sample }
+    /// The number of ticks in a single millisecond.
+    #[inline]
+    pub fn ticks_per_ms(&self) -> f64 { self.ticks_per_ms }
+    /// The number of milliseconds in a single tick.
+    #[inline]
+    pub fn ms_per_tick(&self) -> f64 { self.ms_per_tick }
+
+    /// Convert `Samples` to the nearest `Ticks`.
+    #[inline]
+    pub fn ticks_from_samples(&self, samples: Samples) -> Ticks {
+        Ticks((samples.samples() as f64 * self.ticks_per_sample).round() as calc::Ticks)
+    }
+    /// Convert `Ticks` to `Samples`, truncating towards zero.
+    #[inline]
+    pub fn samples_from_ticks(&self, ticks: Ticks) -> Samples {
+        Samples((ticks.ticks() as f64 * self.samples_per_tick) as calc::Samples)
+    }
+    /// Convert `Samples` to `Ms`.
+    #[inline]
+    pub fn ms_from_samples(&self, samples: Samples) -> Ms {
+        Ms(samples.samples() as calc::Ms * self.ms_per_sample)
+    }
+    /// Convert `Ms` to `Samples`, truncating towards zero.
+    #[inline]
+    pub fn samples_from_ms(&self, ms: Ms) -> Samples {
+        Samples((ms.ms() * self.samples_per_ms) as calc::Samples)
+    }
+    /// Convert `Ticks` to `Ms`.
+    #[inline]
+    pub fn ms_from_ticks(&self, ticks: Ticks) -> Ms {
+        Ms(ticks.ticks() as calc::Ms * self.ms_per_tick)
+    }
+    /// Convert `Ms` to the nearest `Ticks`.
+    #[inline]
+    pub fn ticks_from_ms(&self, ms: Ms) -> Ticks {
+        Ticks((ms.ms() * self.ticks_per_ms).round() as calc::Ticks)
+    }
+
+    /// Convert each of the `Samples` in `src` to `Ticks`, writing the results to `dst`.
+    ///
+    /// **Panics** if `src` and `dst` differ in length.
+    pub fn ticks_from_samples_slice(&self, src: &[Samples], dst: &mut [Ticks]) {
+        assert_eq!(src.len(), dst.len());
+        for (d, &s) in dst.iter_mut().zip(src) {
+            *d = self.ticks_from_samples(s);
+        }
+    }
+    /// Convert each of the `Ticks` in `src` to `Samples`, writing the results to `dst`.
+    ///
+    /// **Panics** if `src` and `dst` differ in length.
+    pub fn samples_from_ticks_slice(&self, src: &[Ticks], dst: &mut [Samples]) {
+        assert_eq!(src.len(), dst.len());
+        for (d, &t) in dst.iter_mut().zip(src) {
+            *d = self.samples_from_ticks(t);
+        }
+    }
+    /// Convert each of the `Samples` in `src` to `Ms`, writing the results to `dst`.
+    ///
+    /// **Panics** if `src` and `dst` differ in length.
+    pub fn ms_from_samples_slice(&self, src: &[Samples], dst: &mut [Ms]) {
+        assert_eq!(src.len(), dst.len());
+        for (d, &s) in dst.iter_mut().zip(src) {
+            *d = self.ms_from_samples(s);
+        }
+    }
+    /// Convert each of the `Ms` in `src` to `Samples`, writing the results to `dst`.
+    ///
+    /// **Panics** if `src` and `dst` differ in length.
+    pub fn samples_from_ms_slice(&self, src: &[Ms], dst: &mut [Samples]) {
+        assert_eq!(src.len(), dst.len());
+        for (d, &ms) in dst.iter_mut().zip(src) {
+            *d = self.samples_from_ms(ms);
+        }
+    }
+    /// Convert each of the `Ticks` in `src` to `Ms`, writing the results to `dst`.
+    ///
+    /// **Panics** if `src` and `dst` differ in length.
+    pub fn ms_from_ticks_slice(&self, src: &[Ticks], dst: &mut [Ms]) {
+        assert_eq!(src.len(), dst.len());
+        for (d, &t) in dst.iter_mut().zip(src) {
+            *d = self.ms_from_ticks(t);
+        }
+    }
+    /// Convert each of the `Ms` in `src` to `Ticks`, writing the results to `dst`.
+    ///
+    /// **Panics** if `src` and `dst` differ in length.
+    pub fn ticks_from_ms_slice(&self, src: &[Ms], dst: &mut [Ticks]) {
+        assert_eq!(src.len(), dst.len());
+        for (d, &ms) in dst.iter_mut().zip(src) {
+            *d = self.ticks_from_ms(ms);
+        }
+    }
+
+}
+
+impl From<TimeContext> for Converter {
+    fn from(ctx: TimeContext) -> Self {
+        Converter::new(ctx.bpm, ctx.ppqn, ctx.sample_hz)
+    }
+}
@@ -0,0 +1,258 @@
+//! CD frames (sectors) and cue sheet `INDEX` and `PREGAP` times.
+//!
+//! CD audio is divided into sectors of 1/75th of a second (588 samples at 44.1kHz), represented
+//! here as `Frames` at `FrameRate::CD`. Cue sheet times are written as `mm:ss:ff`.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use crate::calc;
+use crate::{
+    FrameRate,
+    Frames,
+    Ms,
+    Rounding,
+    SampleHz,
+    Samples,
+};
+
+/// The sample rate of CD audio.
+pub const CD_SAMPLE_HZ: SampleHz = 44_100.0;
+/// The number of CD frames (sectors) per second.
+pub const CD_FRAMES_PER_SECOND: calc::Frames = 75;
+/// The number of samples within a CD frame (sector) at `CD_SAMPLE_HZ`.
+pub const CD_SAMPLES_PER_FRAME: calc::Samples = 588;
+
+/// Snap a position to a CD frame (sector) boundary.
+#[inline]
+pub fn snap_to_cd_frame(samples: Samples, sample_hz: SampleHz, rounding: Rounding) -> Samples {
+    Frames::from_samples(samples, FrameRate::CD, sample_hz, rounding)
+        .to_samples_rounded(FrameRate::CD, sample_hz, Rounding::Nearest)
+}
+
+/// Whether or not a position lies exactly on a CD frame (sector) boundary.
+#[inline]
+pub fn is_on_cd_frame(samples: Samples, sample_hz: SampleHz) -> bool {
+    snap_to_cd_frame(samples, sample_hz, Rounding::Nearest) == samples
+}
+
+/// A time within a cue sheet, written as `mm:ss:ff` where `ff` is in CD frames.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CueTime {
+    pub minutes: u32,
+    pub seconds: u8,
+    pub frames: u8,
+}
+
+impl CueTime {
+
+    /// Construct from a number of CD frames.
+    ///
+    /// Returns `None` if `frames` is negative or too large.
+    pub fn from_frames(frames: Frames) -> Option<CueTime> {
+        let frames = frames.frames();
+        if frames < 0 {
+            return None;
+        }
+        let seconds = frames / CD_FRAMES_PER_SECOND;
+        let minutes = u32::try_from(seconds / 60).ok()?;
+        Some(CueTime {
+            minutes,
+            seconds: (seconds % 60) as u8,
+            frames: (frames % CD_FRAMES_PER_SECOND) as u8,
+        })
+    }
+
+    /// Convert to a number of CD frames.
+    #[inline]
+    pub fn to_frames(&self) -> Frames {
+        let seconds = self.minutes as calc::Frames * 60 + self.seconds as calc::Frames;
+        Frames(seconds * CD_FRAMES_PER_SECOND + self.frames as calc::Frames)
+    }
+
+    /// Construct from a position, snapping it to a CD frame with the given rounding.
+    ///
+    /// Returns `None` if `samples` is negative or too large.
+    #[inline]
+    pub fn from_samples(samples: Samples, sample_hz: SampleHz, rounding: Rounding) -> Option<CueTime> {
+        CueTime::from_frames(Frames::from_samples(samples, FrameRate::CD, sample_hz, rounding))
+    }
+
+    /// Convert to `Samples`.
+    #[inline]
+    pub fn to_samples(&self, sample_hz: SampleHz) -> Samples {
+        self.to_frames().to_samples_rounded(FrameRate::CD, sample_hz, Rounding::Nearest)
+    }
+
+    /// Convert to `Ms`.
+    #[inline]
+    pub fn to_ms(&self) -> Ms {
+        self.to_frames().to_ms(FrameRate::CD)
+    }
+
+}
+
+impl fmt::Display for CueTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:02}:{:02}:{:02}", self.minutes, self.seconds, self.frames)
+    }
+}
+
+impl FromStr for CueTime {
+    type Err = CueParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fields = s.trim().split(':');
+        let mut next = || fields.next().ok_or(CueParseError::InvalidTime);
+        let minutes = parse_number(next()?)?;
+        let seconds = parse_number(next()?)?;
+        let frames = parse_number(next()?)?;
+        if fields.next().is_some() {
+            return Err(CueParseError::InvalidTime);
+        }
+        if seconds >= 60 || frames >= CD_FRAMES_PER_SECOND as u32 {
+            return Err(CueParseError::OutOfRange);
+        }
+        Ok(CueTime { minutes, seconds: seconds as u8, frames: frames as u8 })
+    }
+}
+
+/// A timing line within a cue sheet.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum CueLine {
+    /// `INDEX nn mm:ss:ff`: the position of an index point within the file.
+    Index { number: u8, time: CueTime },
+    /// `PREGAP mm:ss:ff`: the length of silence generated before the track.
+    Pregap(CueTime),
+}
+
+impl fmt::Display for CueLine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CueLine::Index { number, time } => write!(f, "INDEX {:02} {}", number, time),
+            CueLine::Pregap(time) => write!(f, "PREGAP {}", time),
+        }
+    }
+}
+
+impl FromStr for CueLine {
+    type Err = CueParseError;
+    /// Parse a line, ignoring surrounding whitespace and the case of the command.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = s.split_whitespace();
+        let command = words.next().ok_or(CueParseError::MissingField)?;
+        let line = if command.eq_ignore_ascii_case("INDEX") {
+            let number = parse_number(words.next().ok_or(CueParseError::MissingField)?)?;
+            let number = u8::try_from(number).map_err(|_| CueParseError::OutOfRange)?;
+            let time = words.next().ok_or(CueParseError::MissingField)?.parse()?;
+            CueLine::Index { number, time }
+        } else if command.eq_ignore_ascii_case("PREGAP") {
+            CueLine::Pregap(words.next().ok_or(CueParseError::MissingField)?.parse()?)
+        } else {
+            return Err(CueParseError::UnknownCommand);
+        };
+        match words.next() {
+            Some(_) => Err(CueParseError::UnexpectedField),
+            None => Ok(line),
+        }
+    }
+}
+
+/// The reasons a cue sheet line may fail to parse.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CueParseError {
+    /// The line is not an `INDEX` or `PREGAP` command.
+    UnknownCommand,
+    /// The line ended before all fields were read.
+    MissingField,
+    /// The line contained more fields than expected.
+    UnexpectedField,
+    /// A field that should be a number was not.
+    InvalidNumber,
+    /// A time was not of the form `mm:ss:ff`.
+    InvalidTime,
+    /// A number was outside of its valid range.
+    OutOfRange,
+}
+
+impl fmt::Display for CueParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match *self {
+            CueParseError::UnknownCommand => "expected an `INDEX` or `PREGAP` command",
+            CueParseError::MissingField => "missing field",
+            CueParseError::UnexpectedField => "unexpected trailing field",
+            CueParseError::InvalidNumber => "invalid number",
+            CueParseError::InvalidTime => "expected a time of the form `mm:ss:ff`",
+            CueParseError::OutOfRange => "number out of range",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl Error for CueParseError {}
+
+/// Parse an unsigned decimal number, rejecting signs.
+fn parse_number(s: &str) -> Result<u32, CueParseError> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(CueParseError::InvalidNumber);
+    }
+    s.parse().map_err(|_| CueParseError::OutOfRange)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cue_time_round_trip() {
+        for s in ["00:00:00", "01:02:03", "79:59:74", "120:00:00"] {
+            let time: CueTime = s.parse().unwrap();
+            assert_eq!(time.to_string(), s);
+            assert_eq!(CueTime::from_frames(time.to_frames()), Some(time));
+        }
+    }
+
+    #[test]
+    fn cue_time_rejects_invalid() {
+        assert_eq!("00:60:00".parse::<CueTime>(), Err(CueParseError::OutOfRange));
+        assert_eq!("00:00:75".parse::<CueTime>(), Err(CueParseError::OutOfRange));
+        assert_eq!("00:00".parse::<CueTime>(), Err(CueParseError::InvalidTime));
+        assert_eq!("00:00:00:00".parse::<CueTime>(), Err(CueParseError::InvalidTime));
+        assert_eq!("00:-1:00".parse::<CueTime>(), Err(CueParseError::InvalidNumber));
+        assert_eq!(CueTime::from_frames(Frames(-1)), None);
+    }
+
+    #[test]
+    fn cue_line_round_trip() {
+        for s in ["INDEX 01 03:25:12", "INDEX 00 00:00:00", "PREGAP 00:02:00"] {
+            let line: CueLine = s.parse().unwrap();
+            assert_eq!(line.to_string(), s);
+        }
+        let line: CueLine = "  index 1 3:25:12 ".parse().unwrap();
+        assert_eq!(line.to_string(), "INDEX 01 03:25:12");
+    }
+
+    #[test]
+    fn cue_line_rejects_invalid() {
+        assert_eq!("TRACK 01 AUDIO".parse::<CueLine>(), Err(CueParseError::UnknownCommand));
+        assert_eq!("INDEX 01".parse::<CueLine>(), Err(CueParseError::MissingField));
+        assert_eq!("INDEX 256 00:00:00".parse::<CueLine>(), Err(CueParseError::OutOfRange));
+        assert_eq!("PREGAP 00:02:00 00".parse::<CueLine>(), Err(CueParseError::UnexpectedField));
+    }
+
+    #[test]
+    fn cd_frame_boundary() {
+        let one = CueTime { minutes: 0, seconds: 0, frames: 1 };
+        assert_eq!(one.to_samples(CD_SAMPLE_HZ), Samples(CD_SAMPLES_PER_FRAME));
+        assert_eq!(CueTime::from_samples(Samples(587), CD_SAMPLE_HZ, Rounding::Floor).unwrap().frames, 0);
+        assert_eq!(CueTime::from_samples(Samples(588), CD_SAMPLE_HZ, Rounding::Floor), Some(one));
+        assert_eq!(CueTime::from_samples(Samples(587), CD_SAMPLE_HZ, Rounding::Nearest), Some(one));
+        assert!(is_on_cd_frame(Samples(588), CD_SAMPLE_HZ));
+        assert!(!is_on_cd_frame(Samples(587), CD_SAMPLE_HZ));
+        assert!(!is_on_cd_frame(Samples(589), CD_SAMPLE_HZ));
+        assert_eq!(snap_to_cd_frame(Samples(587), CD_SAMPLE_HZ, Rounding::Floor), Samples(0));
+        assert_eq!(snap_to_cd_frame(Samples(587), CD_SAMPLE_HZ, Rounding::Ceil), Samples(588));
+        assert_eq!(snap_to_cd_frame(Samples(589), CD_SAMPLE_HZ, Rounding::Nearest), Samples(588));
+    }
+}
@@ -147,13 +147,15 @@ impl Sub<isize> for Division {
     }
 }
 
-/// The 'Division Type'. Used for handling 'Thirds'.
-/// Whole represents a Whole division, while TwoThirds represents two thirds of a division.
+/// The 'Division Type'. Used for handling 'Thirds' and dotted divisions.
+/// Whole represents a Whole division, TwoThirds represents two thirds of a division (i.e. a
+/// triplet) and Dotted represents one and a half of a division.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum DivType {
     Whole,
     TwoThirds,
+    Dotted,
 }
 
 
@@ -163,6 +165,16 @@ impl DivType {
         FromPrimitive::from_isize(num.to_isize().unwrap()).unwrap()
     }
 
+    /// The multiplier applied to the duration of a division of this type.
+    #[inline]
+    pub fn ratio(&self) -> f64 {
+        match *self {
+            DivType::Whole => 1.0,
+            DivType::TwoThirds => 2.0 / 3.0,
+            DivType::Dotted => 1.5,
+        }
+    }
+
 }
 
 impl NumCast for DivType {
@@ -173,10 +185,10 @@ impl NumCast for DivType {
 
 impl rand::distr::Distribution<DivType> for rand::distr::StandardUniform {
     fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> DivType {
-        if rng.random::<bool>() {
-            DivType::Whole
-        } else {
-            DivType::TwoThirds
+        match rng.random_range(0..3) {
+            0 => DivType::Whole,
+            1 => DivType::TwoThirds,
+            _ => DivType::Dotted,
         }
     }
 }
@@ -187,6 +199,7 @@ impl FromPrimitive for DivType {
         match n {
             0 => Some(DivType::Whole),
             1 => Some(DivType::TwoThirds),
+            2 => Some(DivType::Dotted),
             _ => None,
         }
     }
@@ -195,9 +208,10 @@ impl FromPrimitive for DivType {
 impl ToPrimitive for DivType {
     fn to_i64(&self) -> Option<i64> { self.to_u64().map(|n| n as i64) }
     fn to_u64(&self) -> Option<u64> {
-        Some(match self {
-            &DivType::Whole     => 0,
-            &DivType::TwoThirds => 1,
+        Some(match *self {
+            DivType::Whole     => 0,
+            DivType::TwoThirds => 1,
+            DivType::Dotted    => 2,
         })
     }
 }
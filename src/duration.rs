@@ -0,0 +1,68 @@
+//! Interoperability with `std::time::Duration`.
+
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+use crate::calc;
+
+/// The reasons a time value may fail to convert to a `std::time::Duration`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DurationError {
+    /// `Duration` cannot represent negative values.
+    Negative,
+    /// The value was `NaN` or infinite.
+    NotFinite,
+    /// The value was too large to be represented by the target type.
+    Overflow,
+    /// The sample rate was not positive and finite.
+    InvalidSampleHz,
+}
+
+impl fmt::Display for DurationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match *self {
+            DurationError::Negative => "negative time values cannot be represented as a `Duration`",
+            DurationError::NotFinite => "time value is not finite",
+            DurationError::Overflow => "time value overflows the target type",
+            DurationError::InvalidSampleHz => "sample rate is not positive and finite",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl Error for DurationError {}
+
+/// Check that a sample rate is positive and finite.
+pub(crate) fn check_sample_hz(sample_hz: calc::SampleHz) -> Result<(), DurationError> {
+    if sample_hz.is_finite() && sample_hz > 0.0 {
+        Ok(())
+    } else {
+        Err(DurationError::InvalidSampleHz)
+    }
+}
+
+/// Convert a number of seconds to a `Duration`.
+///
+/// `-0.0` is treated as zero.
+pub(crate) fn duration_from_secs(secs: calc::Seconds) -> Result<Duration, DurationError> {
+    if !secs.is_finite() {
+        return Err(DurationError::NotFinite);
+    }
+    if secs < 0.0 {
+        return Err(DurationError::Negative);
+    }
+    Duration::try_from_secs_f64(secs).map_err(|_| DurationError::Overflow)
+}
+
+/// Convert a signed number of nanoseconds to a `Duration`.
+pub(crate) fn duration_from_nanos(nanos: i128) -> Result<Duration, DurationError> {
+    if nanos < 0 {
+        return Err(DurationError::Negative);
+    }
+    let secs = nanos / calc::SECOND_IN_NS as i128;
+    let subsec = (nanos % calc::SECOND_IN_NS as i128) as u32;
+    if secs > u64::MAX as i128 {
+        return Err(DurationError::Overflow);
+    }
+    Ok(Duration::new(secs as u64, subsec))
+}
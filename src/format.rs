@@ -0,0 +1,417 @@
+//! Human-readable formatting and parsing of durations.
+
+use std::error::Error;
+use std::fmt;
+use crate::calc;
+use crate::{
+    Ms,
+    SampleHz,
+    Samples,
+    SECOND_IN_MS,
+};
+
+/// The greatest number of decimal places shown for seconds.
+const MAX_PRECISION: usize = 9;
+
+/// The layout of a formatted duration.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DurationStyle {
+    /// `1:23.456`, with hours only when required, i.e. `1:02:03.456`.
+    Clock,
+    /// `01:02:03.5`, always showing hours.
+    Hms,
+    /// `2m 3.4s`, omitting leading units that are zero.
+    Compact,
+    /// An ISO 8601 duration, i.e. `PT1M23.456S`.
+    Iso8601,
+}
+
+/// How a negative duration is displayed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum NegativeStyle {
+    /// `-1:23.456`.
+    Minus,
+    /// `(1:23.456)`.
+    Parentheses,
+    /// Negative durations are displayed as zero.
+    Clamp,
+}
+
+/// Describes how durations are displayed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DurationFormat {
+    pub style: DurationStyle,
+    /// The number of decimal places shown for seconds, up to 9.
+    pub precision: usize,
+    /// The minimum number of digits shown for the leading field, padded with zeros.
+    pub width: usize,
+    pub negative: NegativeStyle,
+}
+
+/// A duration that may be displayed with `{}` according to a `DurationFormat`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DisplayDuration<'a> {
+    format: &'a DurationFormat,
+    ms: calc::Ms,
+}
+
+/// The reasons a duration may fail to parse.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ParseDurationError {
+    /// The string contains no duration.
+    Empty,
+    /// A field that should be a number was not.
+    InvalidNumber,
+    /// The string does not match any of the supported formats.
+    InvalidFormat,
+    /// Minutes or seconds following a larger unit were `60` or greater.
+    OutOfRange,
+}
+
+impl fmt::Display for ParseDurationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match *self {
+            ParseDurationError::Empty => "empty duration",
+            ParseDurationError::InvalidNumber => "invalid number",
+            ParseDurationError::InvalidFormat => "unrecognised duration format",
+            ParseDurationError::OutOfRange => "minutes and seconds must be less than 60",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl Error for ParseDurationError {}
+
+impl DurationFormat {
+
+    /// Construct a format of the given style.
+    ///
+    /// Seconds are shown to 3 decimal places, negative durations are prefixed with `-` and the
+    /// leading field is unpadded, except for `DurationStyle::Hms` which pads hours to 2 digits.
+    pub fn new(style: DurationStyle) -> DurationFormat {
+        let width = match style {
+            DurationStyle::Hms => 2,
+            _ => 1,
+        };
+        DurationFormat { style, precision: 3, width, negative: NegativeStyle::Minus }
+    }
+
+    /// Display a duration in `Ms`.
+    #[inline]
+    pub fn ms(&self, ms: Ms) -> DisplayDuration<'_> {
+        DisplayDuration { format: self, ms: ms.ms() }
+    }
+
+    /// Display a duration in `Samples` at the given sample rate.
+    #[inline]
+    pub fn samples(&self, samples: Samples, sample_hz: SampleHz) -> DisplayDuration<'_> {
+        DisplayDuration { format: self, ms: samples.samples() as f64 / sample_hz * SECOND_IN_MS }
+    }
+
+    /// Write the duration to `f`.
+    fn write(&self, ms: calc::Ms, f: &mut fmt::Formatter) -> fmt::Result {
+        if !ms.is_finite() {
+            return write!(f, "{}", ms);
+        }
+        let precision = self.precision.min(MAX_PRECISION);
+        let scale = 10u64.pow(precision as u32);
+        // Round before splitting into fields so that i.e. 59.9996s displays as `1:00.000`.
+        let mut units = (ms.abs() / SECOND_IN_MS * scale as f64).round() as u64;
+        let mut negative = ms < 0.0 && units != 0;
+        if negative && self.negative == NegativeStyle::Clamp {
+            units = 0;
+            negative = false;
+        }
+        let secs = units / scale;
+        let (hours, minutes, seconds) = (secs / 3_600, secs / 60 % 60, secs % 60);
+        let fraction = Fraction { units: units % scale, precision };
+        let width = self.width;
+
+        if negative {
+            match self.negative {
+                NegativeStyle::Parentheses => write!(f, "(")?,
+                _ => write!(f, "-")?,
+            }
+        }
+        match self.style {
+            DurationStyle::Clock if hours == 0 => {
+                write!(f, "{:0w$}:{:02}{}", minutes, seconds, fraction, w = width)?
+            },
+            DurationStyle::Clock | DurationStyle::Hms => {
+                write!(f, "{:0w$}:{:02}:{:02}{}", hours, minutes, seconds, fraction, w = width)?
+            },
+            DurationStyle::Compact => {
+                if hours > 0 {
+                    write!(f, "{:0w$}h {}m {}{}s", hours, minutes, seconds, fraction, w = width)?
+                } else if minutes > 0 {
+                    write!(f, "{:0w$}m {}{}s", minutes, seconds, fraction, w = width)?
+                } else {
+                    write!(f, "{:0w$}{}s", seconds, fraction, w = width)?
+                }
+            },
+            DurationStyle::Iso8601 => {
+                write!(f, "PT")?;
+                if hours > 0 {
+                    write!(f, "{}H", hours)?;
+                }
+                if minutes > 0 {
+                    write!(f, "{}M", minutes)?;
+                }
+                if seconds > 0 || fraction.units > 0 || (hours == 0 && minutes == 0) {
+                    write!(f, "{}{}S", seconds, fraction)?;
+                }
+            },
+        }
+        if negative && self.negative == NegativeStyle::Parentheses {
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+
+}
+
+impl fmt::Display for DisplayDuration<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.format.write(self.ms, f)
+    }
+}
+
+/// The fractional part of a number of seconds, displayed with its leading `.`.
+struct Fraction {
+    units: u64,
+    precision: usize,
+}
+
+impl fmt::Display for Fraction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.precision == 0 {
+            return Ok(());
+        }
+        write!(f, ".{:0p$}", self.units, p = self.precision)
+    }
+}
+
+/// Parse a duration written in any `DurationStyle`.
+///
+/// Negative durations may be prefixed with `-` or surrounded with parentheses. A plain number is
+/// read as seconds. `DurationStyle::Compact` durations may also use the units `d` and `ms`, and
+/// ISO 8601 durations may include days.
+pub fn parse_ms(s: &str) -> Result<Ms, ParseDurationError> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Err(ParseDurationError::Empty);
+    }
+    let (negative, s) = if let Some(inner) = s.strip_prefix('(').and_then(|s| s.strip_suffix(')')) {
+        (true, inner.trim())
+    } else if let Some(rest) = s.strip_prefix('-') {
+        (true, rest)
+    } else {
+        (false, s.strip_prefix('+').unwrap_or(s))
+    };
+    let secs = if let Some(iso) = s.strip_prefix('P').or_else(|| s.strip_prefix('p')) {
+        parse_iso8601(iso)?
+    } else if s.contains(':') {
+        parse_clock(s)?
+    } else if s.ends_with(|c: char| c.is_ascii_alphabetic()) {
+        parse_compact(s)?
+    } else {
+        parse_decimal(s)?
+    };
+    let ms = secs * SECOND_IN_MS;
+    Ok(Ms(if negative { -ms } else { ms }))
+}
+
+/// Parse a duration written in any `DurationStyle` to the nearest `Samples` at the given rate.
+#[inline]
+pub fn parse_samples(s: &str, sample_hz: SampleHz) -> Result<Samples, ParseDurationError> {
+    let ms = parse_ms(s)?;
+    Ok(Samples((ms.ms() * sample_hz / SECOND_IN_MS).round() as calc::Samples))
+}
+
+/// Parse `h:mm:ss.s`, `m:ss.s` or `s.s` into seconds.
+fn parse_clock(s: &str) -> Result<f64, ParseDurationError> {
+    let fields: Vec<&str> = s.split(':').collect();
+    if fields.len() > 3 {
+        return Err(ParseDurationError::InvalidFormat);
+    }
+    let (last, leading) = fields.split_last().ok_or(ParseDurationError::InvalidFormat)?;
+    let mut secs = 0.0;
+    for (i, field) in leading.iter().enumerate() {
+        let value = parse_integer(field)?;
+        if i > 0 && value >= 60.0 {
+            return Err(ParseDurationError::OutOfRange);
+        }
+        secs = secs * 60.0 + value;
+    }
+    let seconds = parse_decimal(last)?;
+    if seconds >= 60.0 {
+        return Err(ParseDurationError::OutOfRange);
+    }
+    Ok(secs * 60.0 + seconds)
+}
+
+/// Parse `1h 2m 3.4s` into seconds.
+fn parse_compact(s: &str) -> Result<f64, ParseDurationError> {
+    components(s)?.into_iter().try_fold(0.0, |secs, (value, unit)| {
+        let unit_secs = match unit.to_ascii_lowercase().as_str() {
+            "d" => 86_400.0,
+            "h" => 3_600.0,
+            "m" => 60.0,
+            "s" => 1.0,
+            "ms" => 1.0 / SECOND_IN_MS,
+            _ => return Err(ParseDurationError::InvalidFormat),
+        };
+        Ok(secs + value * unit_secs)
+    })
+}
+
+/// Parse the part of an ISO 8601 duration following the `P` into seconds.
+fn parse_iso8601(s: &str) -> Result<f64, ParseDurationError> {
+    let (date, time) = match s.find(['T', 't']) {
+        Some(i) => (&s[..i], Some(&s[i + 1..])),
+        None => (s, None),
+    };
+    let date = components(date)?;
+    let time = match time {
+        Some(time) => components(time)?,
+        None => Vec::new(),
+    };
+    if date.is_empty() && time.is_empty() {
+        return Err(ParseDurationError::InvalidFormat);
+    }
+    let mut secs = 0.0;
+    for (value, unit) in date {
+        match unit {
+            "D" | "d" => secs += value * 86_400.0,
+            _ => return Err(ParseDurationError::InvalidFormat),
+        }
+    }
+    // Each designator must follow the one before it.
+    let mut next = 0;
+    for (value, unit) in time {
+        let (index, unit_secs) = match unit {
+            "H" | "h" => (0, 3_600.0),
+            "M" | "m" => (1, 60.0),
+            "S" | "s" => (2, 1.0),
+            _ => return Err(ParseDurationError::InvalidFormat),
+        };
+        if index < next {
+            return Err(ParseDurationError::InvalidFormat);
+        }
+        next = index + 1;
+        secs += value * unit_secs;
+    }
+    Ok(secs)
+}
+
+/// Split a string of the form `1h 2.5m` into its values and units, ignoring whitespace.
+fn components(s: &str) -> Result<Vec<(f64, &str)>, ParseDurationError> {
+    let mut components = Vec::new();
+    let mut rest = s.trim_start();
+    while !rest.is_empty() {
+        let number_end = rest.find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ','))
+            .ok_or(ParseDurationError::InvalidFormat)?;
+        let (number, after) = rest.split_at(number_end);
+        let unit_end = after.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(after.len());
+        let (unit, after) = after.split_at(unit_end);
+        if unit.is_empty() {
+            return Err(ParseDurationError::InvalidFormat);
+        }
+        // ISO 8601 permits a comma as the decimal separator.
+        components.push((parse_decimal(&number.replace(',', "."))?, unit));
+        rest = after.trim_start();
+    }
+    Ok(components)
+}
+
+/// Parse an unsigned whole number.
+fn parse_integer(s: &str) -> Result<f64, ParseDurationError> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(ParseDurationError::InvalidNumber);
+    }
+    s.parse().map_err(|_| ParseDurationError::InvalidNumber)
+}
+
+/// Parse an unsigned decimal number without an exponent.
+fn parse_decimal(s: &str) -> Result<f64, ParseDurationError> {
+    let digits = s.bytes().filter(u8::is_ascii_digit).count();
+    let points = s.bytes().filter(|&b| b == b'.').count();
+    if digits == 0 || points > 1 || digits + points != s.len() {
+        return Err(ParseDurationError::InvalidNumber);
+    }
+    s.parse().map_err(|_| ParseDurationError::InvalidNumber)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STYLES: [DurationStyle; 4] = [
+        DurationStyle::Clock,
+        DurationStyle::Hms,
+        DurationStyle::Compact,
+        DurationStyle::Iso8601,
+    ];
+    const NEGATIVE_STYLES: [NegativeStyle; 3] = [
+        NegativeStyle::Minus,
+        NegativeStyle::Parentheses,
+        NegativeStyle::Clamp,
+    ];
+
+    #[test]
+    fn format_parse_round_trip() {
+        let durations = [0.0, 1.0, 999.0, 59_999.6, 83_456.0, 3_723_456.0, 90_061_001.0];
+        for style in STYLES {
+            for negative in NEGATIVE_STYLES {
+                let format = DurationFormat { negative, ..DurationFormat::new(style) };
+                for ms in durations.iter().flat_map(|&ms| [ms, -ms]) {
+                    let s = format.ms(Ms(ms)).to_string();
+                    let parsed = parse_ms(&s).unwrap_or_else(|e| panic!("{}: {}", s, e)).ms();
+                    let expected = match negative {
+                        NegativeStyle::Clamp => ms.max(0.0),
+                        _ => ms,
+                    };
+                    assert!((parsed - expected.round()).abs() < 1e-6, "{}: {}", s, parsed);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn format_styles() {
+        let expected = ["-1:02:03.456", "-01:02:03.456", "-1h 2m 3.456s", "-PT1H2M3.456S"];
+        for (style, expected) in STYLES.into_iter().zip(expected) {
+            assert_eq!(DurationFormat::new(style).ms(Ms(-3_723_456.0)).to_string(), expected);
+        }
+        let clock = DurationFormat::new(DurationStyle::Clock);
+        let format = DurationFormat { negative: NegativeStyle::Parentheses, ..clock };
+        assert_eq!(format.ms(Ms(-83_456.0)).to_string(), "(1:23.456)");
+        let format = DurationFormat { negative: NegativeStyle::Clamp, ..clock };
+        assert_eq!(format.ms(Ms(-83_456.0)).to_string(), "0:00.000");
+        assert_eq!(clock.ms(Ms(59_999.6)).to_string(), "1:00.000");
+    }
+
+    #[test]
+    fn parse_rejects_invalid() {
+        assert_eq!(parse_ms(""), Err(ParseDurationError::Empty));
+        assert_eq!(parse_ms("PT"), Err(ParseDurationError::InvalidFormat));
+        assert_eq!(parse_ms("P"), Err(ParseDurationError::InvalidFormat));
+        assert_eq!(parse_ms("PT1S2M"), Err(ParseDurationError::InvalidFormat));
+        assert_eq!(parse_ms("1:60"), Err(ParseDurationError::OutOfRange));
+        assert_eq!(parse_ms("1:60:00"), Err(ParseDurationError::OutOfRange));
+        assert_eq!(parse_ms("1:2:3:4"), Err(ParseDurationError::InvalidFormat));
+        assert_eq!(parse_ms("1.2.3"), Err(ParseDurationError::InvalidNumber));
+        assert_eq!(parse_ms("1e3"), Err(ParseDurationError::InvalidNumber));
+        assert_eq!(parse_ms("2x"), Err(ParseDurationError::InvalidFormat));
+    }
+
+    #[test]
+    fn parse_samples_rounds_to_nearest() {
+        assert_eq!(parse_samples("1:30", 48_000.0), Ok(Samples(4_320_000)));
+        assert_eq!(parse_samples("-0.00001", 48_000.0), Ok(Samples(0)));
+        assert_eq!(parse_samples("1.5ms", 44_100.0), Ok(Samples(66)));
+    }
+}
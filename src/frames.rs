@@ -0,0 +1,401 @@
+//! Video frame rates and time in the form of Frames.
+
+use num::{FromPrimitive, ToPrimitive};
+use std::iter::Sum;
+use std::num::NonZeroU32;
+use std::ops::{Add, Sub, Mul, Div, Rem, Neg,
+               AddAssign, SubAssign, MulAssign, DivAssign, RemAssign};
+use crate::calc::{self, RateRatio};
+use crate::{
+    Bpm,
+    Ms,
+    Ppqn,
+    Rounding,
+    SampleHz,
+    Samples,
+    Ticks,
+    SECOND_IN_MS,
+    ms_from_ticks,
+    tick_in_ms,
+};
+
+/// A video frame rate, expressed as an exact ratio of frames per second.
+///
+/// Neither part of the ratio may be `0`, so that every rate has a finite frame duration.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FrameRate {
+    pub num: NonZeroU32,
+    pub den: NonZeroU32,
+}
+
+impl FrameRate {
+
+    /// 24000/1001 frames per second, commonly called 23.976.
+    pub const FPS_23_976: FrameRate = FrameRate::known(24_000, 1_001);
+    pub const FPS_24: FrameRate = FrameRate::known(24, 1);
+    pub const FPS_25: FrameRate = FrameRate::known(25, 1);
+    /// 30000/1001 frames per second, commonly called 29.97.
+    pub const FPS_29_97: FrameRate = FrameRate::known(30_000, 1_001);
+    pub const FPS_30: FrameRate = FrameRate::known(30, 1);
+    pub const FPS_50: FrameRate = FrameRate::known(50, 1);
+    /// 60000/1001 frames per second, commonly called 59.94.
+    pub const FPS_59_94: FrameRate = FrameRate::known(60_000, 1_001);
+    pub const FPS_60: FrameRate = FrameRate::known(60, 1);
+    /// The rate of CD frames (sectors) used by cue sheets.
+    pub const CD: FrameRate = FrameRate::known(75, 1);
+
+    /// Construct a frame rate of `num / den` frames per second.
+    ///
+    /// Returns `None` if either `num` or `den` is `0`.
+    #[inline]
+    pub fn new(num: u32, den: u32) -> Option<FrameRate> {
+        Some(FrameRate { num: NonZeroU32::new(num)?, den: NonZeroU32::new(den)? })
+    }
+
+    /// Construct one of the constant frame rates.
+    const fn known(num: u32, den: u32) -> FrameRate {
+        match (NonZeroU32::new(num), NonZeroU32::new(den)) {
+            (Some(num), Some(den)) => FrameRate { num, den },
+            _ => panic!("a `FrameRate` must not contain 0"),
+        }
+    }
+
+    /// The number of frames per second.
+    #[inline]
+    pub fn fps(&self) -> f64 {
+        self.num.get() as f64 / self.den.get() as f64
+    }
+
+    /// The whole number of frames per second used to label frames within timecode, i.e. `30` for
+    /// 29.97.
+    ///
+    /// Rates below half a frame per second are labelled as `1`.
+    #[inline]
+    pub fn nominal_fps(&self) -> u32 {
+        let (num, den) = (self.num.get() as u64, self.den.get() as u64);
+        ((num + den / 2) / den).max(1) as u32
+    }
+
+    /// The duration of a single frame in milliseconds.
+    #[inline]
+    pub fn frame_in_ms(&self) -> calc::Ms {
+        SECOND_IN_MS * self.den.get() as f64 / self.num.get() as f64
+    }
+
+    /// The rate slowed by 0.1%, i.e. 30 to 29.97, in lowest terms.
+    ///
+    /// Returns `None` if the result does not fit within a `FrameRate`.
+    #[inline]
+    pub fn pull_down(&self) -> Option<FrameRate> {
+        self.scaled(1_000, 1_001)
+    }
+
+    /// The rate sped up by 0.1%, i.e. 29.97 to 30, in lowest terms.
+    ///
+    /// Returns `None` if the result does not fit within a `FrameRate`.
+    #[inline]
+    pub fn pull_up(&self) -> Option<FrameRate> {
+        self.scaled(1_001, 1_000)
+    }
+
+    /// The rate multiplied by `num / den` and reduced to lowest terms.
+    fn scaled(&self, num: u64, den: u64) -> Option<FrameRate> {
+        let num = self.num.get() as u64 * num;
+        let den = self.den.get() as u64 * den;
+        let gcd = num::integer::gcd(num, den);
+        FrameRate::new(u32::try_from(num / gcd).ok()?, u32::try_from(den / gcd).ok()?)
+    }
+
+}
+
+/// The sample rate slowed by 0.1% for film to video transfer, i.e. 48000 to 47952.048.
+#[inline]
+pub fn pull_down_sample_hz(sample_hz: SampleHz) -> SampleHz {
+    sample_hz * 1_000.0 / 1_001.0
+}
+
+/// The sample rate sped up by 0.1% for video to film transfer, i.e. 48000 to 48048.
+#[inline]
+pub fn pull_up_sample_hz(sample_hz: SampleHz) -> SampleHz {
+    sample_hz * 1_001.0 / 1_000.0
+}
+
+/// Time representation in the form of video Frames at some `FrameRate`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Frames(pub calc::Frames);
+
+impl Frames {
+
+    /// Return the unit value of Frames.
+    #[inline]
+    pub fn frames(&self) -> calc::Frames { let Frames(frames) = *self; frames }
+
+    /// Convert to the unit value of `Ms`.
+    #[inline]
+    pub fn ms(&self, rate: FrameRate) -> calc::Ms {
+        self.frames() as calc::Ms * rate.frame_in_ms()
+    }
+    /// Convert to `Ms`.
+    #[inline]
+    pub fn to_ms(&self, rate: FrameRate) -> Ms {
+        Ms(self.ms(rate))
+    }
+    /// Convert from `Ms` using the given rounding.
+    #[inline]
+    pub fn from_ms(ms: Ms, rate: FrameRate, rounding: Rounding) -> Frames {
+        Frames(rounding.round(ms.ms() / rate.frame_in_ms()) as calc::Frames)
+    }
+
+    /// Convert to the unit value of `Samples`, truncating towards zero.
+    #[inline]
+    pub fn samples(&self, rate: FrameRate, sample_hz: SampleHz) -> calc::Samples {
+        self.to_samples_rounded(rate, sample_hz, Rounding::Truncate).samples()
+    }
+    /// Convert to `Samples`, truncating towards zero.
+    #[inline]
+    pub fn to_samples(&self, rate: FrameRate, sample_hz: SampleHz) -> Samples {
+        Samples(self.samples(rate, sample_hz))
+    }
+    /// Convert to `Samples` using the given rounding.
+    ///
+    /// Whole number sample rates are converted with exact integer math.
+    #[inline]
+    pub fn to_samples_rounded(&self, rate: FrameRate, sample_hz: SampleHz,
+                              rounding: Rounding) -> Samples {
+        let ratio = RateRatio::new(rate.num.get() as f64, sample_hz * rate.den.get() as f64);
+        Samples(ratio.apply(self.frames(), rounding))
+    }
+    /// Convert from `Samples` using the given rounding.
+    ///
+    /// Whole number sample rates are converted with exact integer math.
+    #[inline]
+    pub fn from_samples(samples: Samples, rate: FrameRate, sample_hz: SampleHz,
+                        rounding: Rounding) -> Frames {
+        let ratio = RateRatio::new(sample_hz * rate.den.get() as f64, rate.num.get() as f64);
+        Frames(ratio.apply(samples.samples(), rounding))
+    }
+
+    /// Convert to the unit value of `Ticks`, rounding to the nearest tick.
+    #[inline]
+    pub fn ticks(&self, rate: FrameRate, bpm: impl Into<Bpm>, ppqn: Ppqn) -> calc::Ticks {
+        (self.ms(rate) / tick_in_ms(bpm, ppqn)).round() as calc::Ticks
+    }
+    /// Convert to `Ticks`, rounding to the nearest tick.
+    #[inline]
+    pub fn to_ticks(&self, rate: FrameRate, bpm: impl Into<Bpm>, ppqn: Ppqn) -> Ticks {
+        Ticks(self.ticks(rate, bpm, ppqn))
+    }
+    /// Convert from `Ticks` using the given rounding.
+    #[inline]
+    pub fn from_ticks(ticks: Ticks, rate: FrameRate, bpm: impl Into<Bpm>, ppqn: Ppqn,
+                      rounding: Rounding) -> Frames {
+        Frames::from_ms(Ms(ms_from_ticks(ticks.ticks(), bpm, ppqn)), rate, rounding)
+    }
+
+    /// The frame nearest to the given musical position, i.e. for placing clicks on frames.
+    #[inline]
+    pub fn nearest_to_ticks(ticks: Ticks, rate: FrameRate, bpm: impl Into<Bpm>, ppqn: Ppqn) -> Frames {
+        Frames::from_ticks(ticks, rate, bpm, ppqn, Rounding::Nearest)
+    }
+
+    /// Checked addition. Returns `None` if overflow occurred.
+    #[inline]
+    pub fn checked_add(&self, rhs: Self) -> Option<Self> {
+        self.frames().checked_add(rhs.frames()).map(Self)
+    }
+    /// Checked subtraction. Returns `None` if overflow occurred.
+    #[inline]
+    pub fn checked_sub(&self, rhs: Self) -> Option<Self> {
+        self.frames().checked_sub(rhs.frames()).map(Self)
+    }
+    /// Checked multiplication by a scalar. Returns `None` if overflow occurred.
+    #[inline]
+    pub fn checked_mul(&self, rhs: calc::Frames) -> Option<Self> {
+        self.frames().checked_mul(rhs).map(Self)
+    }
+    /// Checked division by a scalar. Returns `None` if `rhs == 0` or overflow occurred.
+    #[inline]
+    pub fn checked_div(&self, rhs: calc::Frames) -> Option<Self> {
+        self.frames().checked_div(rhs).map(Self)
+    }
+
+    /// Saturating addition. Clamps the result at the numeric bounds instead of overflowing.
+    #[inline]
+    pub fn saturating_add(&self, rhs: Self) -> Self {
+        Self(self.frames().saturating_add(rhs.frames()))
+    }
+    /// Saturating subtraction. Clamps the result at the numeric bounds instead of overflowing.
+    #[inline]
+    pub fn saturating_sub(&self, rhs: Self) -> Self {
+        Self(self.frames().saturating_sub(rhs.frames()))
+    }
+    /// Saturating multiplication by a scalar. Clamps the result at the numeric bounds instead of
+    /// overflowing.
+    #[inline]
+    pub fn saturating_mul(&self, rhs: calc::Frames) -> Self {
+        Self(self.frames().saturating_mul(rhs))
+    }
+
+}
+
+impl From<calc::Frames> for Frames {
+    #[inline]
+    fn from(frames: calc::Frames) -> Self {
+        Frames(frames)
+    }
+}
+
+impl Add for Frames {
+    type Output = Self;
+    #[inline]
+    fn add(self, rhs: Self) -> Frames {
+        Frames(self.frames() + rhs.frames())
+    }
+}
+
+impl Sub for Frames {
+    type Output = Self;
+    #[inline]
+    fn sub(self, rhs: Self) -> Frames {
+        Frames(self.frames() - rhs.frames())
+    }
+}
+
+impl Mul<calc::Frames> for Frames {
+    type Output = Self;
+    #[inline]
+    fn mul(self, rhs: calc::Frames) -> Self {
+        Self(self.frames() * rhs)
+    }
+}
+
+impl Mul<Frames> for calc::Frames {
+    type Output = Frames;
+    #[inline]
+    fn mul(self, rhs: Frames) -> Frames {
+        Frames(self * rhs.frames())
+    }
+}
+
+impl Div<calc::Frames> for Frames {
+    type Output = Self;
+    #[inline]
+    fn div(self, rhs: calc::Frames) -> Self {
+        Self(self.frames() / rhs)
+    }
+}
+
+impl Div for Frames {
+    type Output = f64;
+    #[inline]
+    fn div(self, rhs: Self) -> f64 {
+        self.frames() as f64 / rhs.frames() as f64
+    }
+}
+
+impl Rem for Frames {
+    type Output = Self;
+    #[inline]
+    fn rem(self, rhs: Self) -> Frames {
+        Frames(self.frames() % rhs.frames())
+    }
+}
+
+impl Neg for Frames {
+    type Output = Self;
+    #[inline]
+    fn neg(self) -> Frames {
+        Frames(-self.frames())
+    }
+}
+
+impl AddAssign for Frames {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Frames {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign<calc::Frames> for Frames {
+    fn mul_assign(&mut self, rhs: calc::Frames) {
+        *self = *self * rhs;
+    }
+}
+
+impl DivAssign<calc::Frames> for Frames {
+    fn div_assign(&mut self, rhs: calc::Frames) {
+        *self = *self / rhs;
+    }
+}
+
+impl RemAssign for Frames {
+    fn rem_assign(&mut self, rhs: Self) {
+        *self = *self % rhs;
+    }
+}
+
+impl Sum for Frames {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Frames(0), |total, x| total + x)
+    }
+}
+
+impl<'a> Sum<&'a Frames> for Frames {
+    fn sum<I: Iterator<Item = &'a Frames>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+impl ToPrimitive for Frames {
+    fn to_u64(&self) -> Option<u64> {
+        self.frames().to_u64()
+    }
+    fn to_i64(&self) -> Option<i64> {
+        self.frames().to_i64()
+    }
+}
+
+impl FromPrimitive for Frames {
+    fn from_u64(n: u64) -> Option<Frames> {
+        Some(Frames(n as calc::Frames))
+    }
+    fn from_i64(n: i64) -> Option<Frames> {
+        Some(Frames(n as calc::Frames))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_rate_rejects_zero() {
+        assert_eq!(FrameRate::new(30, 0), None);
+        assert_eq!(FrameRate::new(0, 1), None);
+        assert_eq!(FrameRate::new(30, 1), Some(FrameRate::FPS_30));
+    }
+
+    #[test]
+    fn nominal_fps() {
+        assert_eq!(FrameRate::FPS_29_97.nominal_fps(), 30);
+        assert_eq!(FrameRate::FPS_23_976.nominal_fps(), 24);
+        assert_eq!(FrameRate::new(u32::MAX, 1).unwrap().nominal_fps(), u32::MAX);
+        assert_eq!(FrameRate::new(1, u32::MAX).unwrap().nominal_fps(), 1);
+    }
+
+    #[test]
+    fn pull_round_trip() {
+        assert_eq!(FrameRate::FPS_29_97.pull_up(), Some(FrameRate::FPS_30));
+        assert_eq!(FrameRate::FPS_30.pull_down(), Some(FrameRate::FPS_29_97));
+        let twice = FrameRate::FPS_30.pull_down().and_then(|rate| rate.pull_down()).unwrap();
+        assert_eq!(twice.pull_up().and_then(|rate| rate.pull_up()), Some(FrameRate::FPS_30));
+        assert_eq!(FrameRate::new(u32::MAX, 1).unwrap().pull_up(), None);
+    }
+}
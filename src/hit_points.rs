@@ -0,0 +1,254 @@
+//! Solving for a tempo at which hit points (i.e. cues within a film) land on the beat.
+
+use std::cmp::Ordering;
+use crate::calc;
+use crate::{
+    Bpm,
+    Division,
+    DivType,
+    FrameRate,
+    Measure,
+    Ms,
+    NumDiv,
+    Timecode,
+    TimeSig,
+    beat_in_ms,
+};
+
+/// The maximum number of tempos tried by `HitPointSolver::solve`.
+const MAX_STEPS: f64 = 100_000.0;
+
+/// Searches a range of tempos for those at which the most hit points land on a musical grid.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HitPointSolver {
+    pub ts: TimeSig,
+    pub min_bpm: Bpm,
+    pub max_bpm: Bpm,
+    /// The interval between each tempo that is tried.
+    pub bpm_step: Bpm,
+    /// A hit lands on the grid if it is within this distance of a grid line.
+    pub tolerance: Ms,
+    /// The spacing of the grid lines on which hits should land.
+    pub division: Division,
+    /// The frame rate of the picture, used to report errors in frames and to read timecode.
+    pub frame_rate: FrameRate,
+    /// The time of the first downbeat. If `None`, the offset is solved for each tempo.
+    pub offset: Option<Ms>,
+    /// The maximum number of solutions returned.
+    pub max_solutions: usize,
+}
+
+/// A tempo and offset proposed by the `HitPointSolver`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HitSolution {
+    pub bpm: Bpm,
+    /// The time of the first downbeat.
+    pub offset: Ms,
+    /// The number of hits within the tolerance of a grid line.
+    pub hits_on_grid: usize,
+    /// The sum of the distances from each hit to its nearest grid line.
+    pub total_error: Ms,
+    /// Each hit's position and error, in the order given.
+    pub hits: Vec<HitReport>,
+}
+
+/// How well the hits land on the grid at a tempo, before each hit is reported.
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Score {
+    bpm: Bpm,
+    offset: calc::Ms,
+    hits_on_grid: usize,
+    total_error: calc::Ms,
+}
+
+/// The position of a hit point under a `HitSolution`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct HitReport {
+    /// The time of the hit.
+    pub ms: Ms,
+    /// The bar in which the hit falls, where the bar beginning at the offset is `0`.
+    pub bar: NumDiv,
+    /// The position of the hit within its bar in beats.
+    pub beat: f64,
+    /// The distance from the nearest grid line to the hit. Negative if the hit is early.
+    pub error: Ms,
+    /// `error` in frames.
+    pub error_frames: f64,
+    /// Whether or not the hit is within the tolerance of a grid line.
+    pub on_grid: bool,
+}
+
+impl HitPointSolver {
+
+    /// Construct a solver for the given meter and tempo range.
+    ///
+    /// Tempos are tried every 0.01 BPM, hits must land within 10ms of a beat, the picture runs at
+    /// 24 frames per second and the offset is solved for.
+    pub fn new(ts: TimeSig, min_bpm: Bpm, max_bpm: Bpm) -> HitPointSolver {
+        HitPointSolver {
+            ts,
+            min_bpm,
+            max_bpm,
+            bpm_step: 0.01,
+            tolerance: Ms(10.0),
+            division: Division::Beat,
+            frame_rate: FrameRate::FPS_24,
+            offset: None,
+            max_solutions: 10,
+        }
+    }
+
+    /// Rank the tempos within the range by how many hits land on the grid, breaking ties by the
+    /// smallest total error.
+    ///
+    /// Only tempos that rank better than their neighbouring steps are returned, so that each
+    /// solution is distinct rather than a step away from a better one.
+    ///
+    /// Returns no solutions if the range and step are not positive and finite, if `min_bpm` is
+    /// not below `max_bpm` or if the range spans more than 100000 steps.
+    pub fn solve(&self, hits: &[Ms]) -> Vec<HitSolution> {
+        let valid_range = self.min_bpm > 0.0 && self.min_bpm < self.max_bpm
+            && self.max_bpm.is_finite() && self.bpm_step > 0.0 && self.bpm_step.is_finite();
+        if hits.is_empty() || !valid_range {
+            return Vec::new();
+        }
+        let steps = ((self.max_bpm - self.min_bpm) / self.bpm_step).floor();
+        if steps > MAX_STEPS {
+            return Vec::new();
+        }
+        let scores: Vec<Score> = (0..=steps as usize)
+            .map(|i| self.score_bpm(hits, self.min_bpm + i as f64 * self.bpm_step))
+            .collect();
+        // Keep the first step of each plateau that ranks at least as well as both neighbours.
+        let mut best: Vec<Score> = (0..scores.len())
+            .filter(|&i| {
+                let after_prev = i == 0 || rank(&scores[i], &scores[i - 1]) == Ordering::Less;
+                let before_next = i + 1 == scores.len()
+                    || rank(&scores[i], &scores[i + 1]) != Ordering::Greater;
+                after_prev && before_next
+            })
+            .map(|i| scores[i])
+            .collect();
+        best.sort_by(rank);
+        best.truncate(self.max_solutions);
+        best.into_iter().map(|score| self.report(hits, score)).collect()
+    }
+
+    /// Rank the tempos within the range for hits given as timecode, measured from the timecode
+    /// at which the picture starts.
+    pub fn solve_timecode(&self, hits: &[Timecode], start: Timecode) -> Vec<HitSolution> {
+        let start = start.to_frames(self.frame_rate);
+        let hits: Vec<Ms> = hits.iter()
+            .map(|hit| (hit.to_frames(self.frame_rate) - start).to_ms(self.frame_rate))
+            .collect();
+        self.solve(&hits)
+    }
+
+    /// Evaluate the hits at the given tempo.
+    #[inline]
+    pub fn solve_bpm(&self, hits: &[Ms], bpm: impl Into<Bpm>) -> HitSolution {
+        self.report(hits, self.score_bpm(hits, bpm.into()))
+    }
+
+    /// Find the offset at which the most hits land on the grid at the given tempo.
+    fn score_bpm(&self, hits: &[Ms], bpm: Bpm) -> Score {
+        let grid = Measure(1, self.division, DivType::Whole).ms(bpm, self.ts);
+        let offset = match self.offset {
+            Some(offset) => offset.ms(),
+            // Try aligning each hit exactly to the grid, keeping the best.
+            None => hits.iter()
+                .map(|hit| hit.ms().rem_euclid(grid))
+                .map(|offset| (offset, self.score(hits, grid, offset)))
+                .min_by(|a, b| b.1.0.cmp(&a.1.0).then(a.1.1.total_cmp(&b.1.1)))
+                .map(|(offset, _)| offset)
+                .unwrap_or(0.0),
+        };
+        let (hits_on_grid, total_error) = self.score(hits, grid, offset);
+        Score { bpm, offset, hits_on_grid, total_error }
+    }
+
+    /// Report the position and error of each hit under the given score.
+    fn report(&self, hits: &[Ms], score: Score) -> HitSolution {
+        let Score { bpm, offset, hits_on_grid, total_error } = score;
+        let grid = Measure(1, self.division, DivType::Whole).ms(bpm, self.ts);
+        let beat = beat_in_ms(bpm);
+        let beats_per_bar = self.ts.beats_per_bar();
+        let reports = hits.iter()
+            .map(|&ms| {
+                let error = grid_error(ms.ms(), grid, offset);
+                let beats = (ms.ms() - offset) / beat;
+                let bar = (beats / beats_per_bar).floor();
+                HitReport {
+                    ms,
+                    bar: bar as NumDiv,
+                    beat: beats - bar * beats_per_bar,
+                    error: Ms(error),
+                    error_frames: error / self.frame_rate.frame_in_ms(),
+                    on_grid: error.abs() <= self.tolerance.ms(),
+                }
+            })
+            .collect();
+        HitSolution {
+            bpm,
+            offset: Ms(offset),
+            hits_on_grid,
+            total_error: Ms(total_error),
+            hits: reports,
+        }
+    }
+
+    /// The number of hits on the grid along with the total error.
+    fn score(&self, hits: &[Ms], grid: calc::Ms, offset: calc::Ms) -> (usize, calc::Ms) {
+        hits.iter().fold((0, 0.0), |(on_grid, total), hit| {
+            let error = grid_error(hit.ms(), grid, offset).abs();
+            let on_grid = if error <= self.tolerance.ms() { on_grid + 1 } else { on_grid };
+            (on_grid, total + error)
+        })
+    }
+
+}
+
+/// Orders the better of two scores first: more hits on the grid, then less total error.
+fn rank(a: &Score, b: &Score) -> Ordering {
+    b.hits_on_grid.cmp(&a.hits_on_grid).then(a.total_error.total_cmp(&b.total_error))
+}
+
+/// The signed distance from the nearest grid line to `ms`.
+fn grid_error(ms: calc::Ms, grid: calc::Ms, offset: calc::Ms) -> calc::Ms {
+    let from_line = (ms - offset).rem_euclid(grid);
+    if from_line > grid / 2.0 { from_line - grid } else { from_line }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HITS: [Ms; 4] = [Ms(1_000.0), Ms(3_000.0), Ms(4_500.0), Ms(6_000.0)];
+
+    #[test]
+    fn solutions_are_local_maxima() {
+        let solver = HitPointSolver::new(TimeSig { top: 4, bottom: 4 }, 100.0, 140.0);
+        let solutions = solver.solve(&HITS);
+        assert_eq!(solutions[0].bpm, 120.0);
+        assert_eq!(solutions[0].hits_on_grid, 4);
+        assert_eq!(solutions[0].hits.len(), HITS.len());
+        assert!(solutions.iter().skip(1).all(|s| (s.bpm - 120.0).abs() > 0.011));
+    }
+
+    #[test]
+    fn rejects_invalid_ranges() {
+        let solver = HitPointSolver::new(TimeSig { top: 4, bottom: 4 }, 100.0, 140.0);
+        let invalid = [
+            HitPointSolver { bpm_step: 1e-9, ..solver },
+            HitPointSolver { bpm_step: f64::NAN, ..solver },
+            HitPointSolver { max_bpm: f64::INFINITY, ..solver },
+            HitPointSolver { max_bpm: f64::NAN, ..solver },
+            HitPointSolver { min_bpm: 0.0, ..solver },
+            HitPointSolver { min_bpm: 140.0, max_bpm: 100.0, ..solver },
+        ];
+        for solver in invalid {
+            assert!(solver.solve(&HITS).is_empty(), "{:?}", solver);
+        }
+    }
+}
@@ -4,42 +4,117 @@ extern crate rand;
 
 pub use bars::Bars;
 pub use beats::Beats;
+pub use self::beat_grid::{BeatGrid, BeatMatch};
+pub use self::bwf::{BwfError, Timecode, TimeReference};
 pub use self::calc::{
     Bpm,
     Ppqn,
+    Rounding,
     SampleHz,
     MINUTE_IN_MS,
+    MS_IN_NS,
     SECOND_IN_MS,
+    SECOND_IN_NS,
     bar_in_ms,
     beat_in_ms,
+    common_ppqn,
     ms_from_measure,
     ms_from_samples,
     ms_from_ticks,
     samples_from_measure,
     samples_from_ms,
+    samples_from_sample_hz,
     samples_from_ticks,
     tick_in_ms,
     ticks_from_measure,
     ticks_from_ms,
+    ticks_from_ppqn,
     ticks_from_samples,
 };
+pub use self::context::{TimeContext, TimeUnit};
+pub use self::converter::Converter;
+pub use self::cue::{
+    CueLine,
+    CueParseError,
+    CueTime,
+    CD_FRAMES_PER_SECOND,
+    CD_SAMPLES_PER_FRAME,
+    CD_SAMPLE_HZ,
+    is_on_cd_frame,
+    snap_to_cd_frame,
+};
+pub use self::duration::DurationError;
 pub use self::division::{
     Division,
     DivType,
     NumDiv,
 };
+pub use self::format::{
+    parse_ms,
+    parse_samples,
+    DisplayDuration,
+    DurationFormat,
+    DurationStyle,
+    NegativeStyle,
+    ParseDurationError,
+};
+pub use self::frames::{pull_down_sample_hz, pull_up_sample_hz, FrameRate, Frames};
+pub use self::hit_points::{HitPointSolver, HitReport, HitSolution};
+pub use self::loop_tempo::{bpm_for_loop, loop_candidates, LoopCandidate};
+pub use self::midi_tempo::{MidiTempo, RoundedTempo};
 pub use self::ms::Ms;
-pub use self::samples::Samples;
+pub use self::nanos::Nanos;
+pub use self::phase::{fill_phase, phase_at_beats, phase_at_ticks};
+pub use self::remap::{Position, TempoRemap, TimeDomain};
+pub use self::rubato::{RubatoBuilder, RubatoCurve, RubatoError, RubatoMap};
+pub use self::samples::{Oversampling, Samples};
 pub use self::measure::Measure;
-pub use self::ticks::Ticks;
+pub use self::seconds::Seconds;
+pub use self::sync::{
+    frequency_hz,
+    nearest_measure_from_hz,
+    nearest_measure_from_ms,
+    period_ms,
+    period_samples,
+    sync_table,
+    SyncEntry,
+};
+pub use self::tap_tempo::{TapEstimate, TapTempo, TempoSnap};
+pub use self::tempo::{metric_modulation, Tempo};
+pub use self::tempo_estimate::{estimate_tempo, TempoCandidate};
+pub use self::tempo_map::{TempoChange, TempoMap, TempoMapError};
+pub use self::ticks::{PpqnTicks, Rescaled, Ticks};
 pub use self::time_sig::TimeSig;
+pub use self::warp_map::{WarpError, WarpMap, WarpMarker, WarpSegment};
 
 pub mod bars;
+pub mod beat_grid;
 pub mod beats;
+pub mod bwf;
 pub mod calc;
+pub mod context;
+pub mod converter;
+pub mod cue;
 pub mod division;
+pub mod duration;
+pub mod format;
+pub mod frames;
+pub mod hit_points;
+pub mod loop_tempo;
 pub mod measure;
+pub mod midi_tempo;
 pub mod ms;
+pub mod nanos;
+pub mod phase;
+pub mod remap;
+pub mod rubato;
 pub mod samples;
+pub mod seconds;
+pub mod sync;
+pub mod tap_tempo;
+pub mod tempo;
+pub mod tempo_estimate;
+pub mod tempo_map;
 pub mod ticks;
 pub mod time_sig;
+pub mod warp_map;
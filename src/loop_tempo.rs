@@ -0,0 +1,60 @@
+//! Deriving the tempo of a loop from its length.
+
+use crate::{
+    Bars,
+    Bpm,
+    NumDiv,
+    SampleHz,
+    Samples,
+    TimeSig,
+    MINUTE_IN_MS,
+    SECOND_IN_MS,
+};
+
+/// The fastest tempo considered by `loop_candidates`.
+const MAX_BPM: Bpm = 1_000.0;
+/// The greatest number of bars considered by `loop_candidates`.
+const MAX_BARS: NumDiv = 65_536;
+
+/// A possible interpretation of a loop's length proposed by `loop_candidates`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LoopCandidate {
+    /// The number of bars the loop covers.
+    pub bars: Bars,
+    /// The tempo at which the loop lasts exactly `bars`.
+    pub bpm: Bpm,
+}
+
+/// The tempo at which the given number of bars lasts exactly the given number of samples.
+#[inline]
+pub fn bpm_for_loop(bars: Bars, ts: TimeSig, samples: Samples, sample_hz: SampleHz) -> Bpm {
+    let beats = bars.bars() as f64 * ts.beats_per_bar();
+    beats * MINUTE_IN_MS * sample_hz / (samples.samples() as f64 * SECOND_IN_MS)
+}
+
+/// List every whole number of bars that a loop of the given length could cover with a tempo
+/// within `min_bpm..=max_bpm`.
+///
+/// Power of two bar counts are the most common loop lengths, so these are listed first. Each group
+/// is ordered by ascending bar count.
+///
+/// Tempos above 1000 BPM and counts above 65536 bars are not considered. Returns an empty list if
+/// the range is not positive, finite and ascending.
+pub fn loop_candidates(samples: Samples, sample_hz: SampleHz, ts: TimeSig,
+                       min_bpm: Bpm, max_bpm: Bpm) -> Vec<LoopCandidate> {
+    let valid_range = min_bpm > 0.0 && min_bpm <= max_bpm && max_bpm.is_finite();
+    if samples.samples() <= 0 || !valid_range {
+        return Vec::new();
+    }
+    let bpm_per_bar = bpm_for_loop(Bars(1), ts, samples, sample_hz);
+    if !(bpm_per_bar > 0.0 && bpm_per_bar.is_finite()) {
+        return Vec::new();
+    }
+    let first = (min_bpm / bpm_per_bar).ceil().max(1.0) as NumDiv;
+    let last = ((max_bpm.min(MAX_BPM) / bpm_per_bar).floor() as NumDiv).min(MAX_BARS);
+    let mut candidates: Vec<LoopCandidate> = (first..=last)
+        .map(|n| LoopCandidate { bars: Bars(n), bpm: bpm_for_loop(Bars(n), ts, samples, sample_hz) })
+        .collect();
+    candidates.sort_by_key(|c| (!(c.bars.bars() as u64).is_power_of_two(), c.bars));
+    candidates
+}
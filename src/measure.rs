@@ -30,6 +30,7 @@ use super::{
 /// Measure(1, Bar, Whole) is one bar of musical time.
 /// Measure(3, Beat, Whole) is three beats of musical time.
 /// Measure(1, Minim, TwoThirds) is two thirds of a minim.
+/// Measure(1, Quaver, Dotted) is a dotted quaver.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Measure(pub NumDiv, pub Division, pub DivType);
@@ -59,10 +60,7 @@ impl Measure {
     #[inline]
     pub fn beats(&self, ts: TimeSig) -> f64 {
         let Measure(num, div, div_type) = *self;
-        match div_type {
-            DivType::Whole => div.beats(ts) * num as f64,
-            DivType::TwoThirds => div.beats(ts) * num as f64 * (2.0 / 3.0),
-        }
+        div.beats(ts) * num as f64 * div_type.ratio()
     }
     /// Convert to the equivalent duration in Bars.
     #[inline]
@@ -72,25 +70,25 @@ impl Measure {
 
     /// Convert to the unit value of `Ms`.
     #[inline]
-    pub fn ms(&self, bpm: Bpm, ts: TimeSig) -> calc::Ms {
+    pub fn ms(&self, bpm: impl Into<Bpm>, ts: TimeSig) -> calc::Ms {
         let Measure(num, div, div_type) = *self;
         ms_from_measure(num, div, div_type, bpm, ts)
     }
     /// Convert to `Ms`.
     #[inline]
-    pub fn to_ms(&self, bpm: Bpm, ts: TimeSig) -> Ms {
+    pub fn to_ms(&self, bpm: impl Into<Bpm>, ts: TimeSig) -> Ms {
         Ms(self.ms(bpm, ts))
     }
 
     /// Convert to the unit value of `Samples`.
     #[inline]
-    pub fn samples(&self, bpm: Bpm, ts: TimeSig, sample_hz: SampleHz) -> calc::Samples {
+    pub fn samples(&self, bpm: impl Into<Bpm>, ts: TimeSig, sample_hz: SampleHz) -> calc::Samples {
         let Measure(num, div, div_type) = *self;
         samples_from_measure(num, div, div_type, bpm, ts, sample_hz)
     }
     /// Convert to `Samples`.
     #[inline]
-    pub fn to_samples(&self, bpm: Bpm, ts: TimeSig, sample_hz: SampleHz) -> Samples {
+    pub fn to_samples(&self, bpm: impl Into<Bpm>, ts: TimeSig, sample_hz: SampleHz) -> Samples {
         Samples(self.samples(bpm, ts, sample_hz))
     }
 
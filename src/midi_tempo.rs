@@ -0,0 +1,93 @@
+//! Tempo in the form of microseconds per quarter note, as used by Standard MIDI File Set Tempo
+//! meta events.
+
+use crate::calc;
+use crate::{
+    Bpm,
+    Rounding,
+};
+
+/// The number of microseconds within a minute.
+const MINUTE_IN_MICROS: f64 = 60_000_000.0;
+
+/// Tempo in the form of microseconds per quarter note.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MidiTempo(pub u32);
+
+/// A `MidiTempo` converted from `Bpm` along with the error introduced by rounding.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RoundedTempo {
+    /// The converted tempo.
+    pub tempo: MidiTempo,
+    /// The original `Bpm` minus the `Bpm` represented by `tempo`.
+    pub error: Bpm,
+}
+
+impl MidiTempo {
+
+    /// The greatest tempo value representable by a Set Tempo meta event.
+    pub const MAX: MidiTempo = MidiTempo(0xFF_FFFF);
+    /// The default tempo of a Standard MIDI File, 120 BPM.
+    pub const DEFAULT: MidiTempo = MidiTempo(500_000);
+
+    /// Return the number of microseconds per quarter note.
+    #[inline]
+    pub fn micros(&self) -> u32 { let MidiTempo(micros) = *self; micros }
+
+    /// Convert to the unit value of `Bpm`.
+    #[inline]
+    pub fn bpm(&self) -> Bpm {
+        MINUTE_IN_MICROS / self.micros() as f64
+    }
+
+    /// The duration of a quarter note in milliseconds.
+    #[inline]
+    pub fn beat_in_ms(&self) -> calc::Ms {
+        self.micros() as f64 / 1_000.0
+    }
+
+    /// Convert from `Bpm` using the given rounding, reporting the error introduced.
+    ///
+    /// The result is clamped to `1..=MidiTempo::MAX`. Returns `None` if `bpm` is not positive and
+    /// finite.
+    pub fn from_bpm(bpm: impl Into<Bpm>, rounding: Rounding) -> Option<RoundedTempo> {
+        let bpm = bpm.into();
+        if !(bpm.is_finite() && bpm > 0.0) {
+            return None;
+        }
+        let micros = rounding.round(MINUTE_IN_MICROS / bpm).clamp(1.0, MidiTempo::MAX.micros() as f64);
+        let tempo = MidiTempo(micros as u32);
+        Some(RoundedTempo { tempo, error: bpm - tempo.bpm() })
+    }
+
+    /// Decode the 24-bit big-endian value of a Set Tempo meta event.
+    #[inline]
+    pub fn from_bytes(bytes: [u8; 3]) -> MidiTempo {
+        MidiTempo(u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]))
+    }
+
+    /// Encode as the 24-bit big-endian value of a Set Tempo meta event.
+    ///
+    /// Values greater than `MidiTempo::MAX` saturate.
+    #[inline]
+    pub fn to_bytes(&self) -> [u8; 3] {
+        let [_, a, b, c] = self.micros().min(MidiTempo::MAX.micros()).to_be_bytes();
+        [a, b, c]
+    }
+
+}
+
+impl RoundedTempo {
+
+    /// Whether or not the tempo represents the original `Bpm` exactly.
+    #[inline]
+    pub fn is_exact(&self) -> bool { self.error == 0.0 }
+
+}
+
+impl Default for MidiTempo {
+    fn default() -> Self {
+        MidiTempo::DEFAULT
+    }
+}
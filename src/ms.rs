@@ -6,9 +6,14 @@
 //!
 
 use num::{FromPrimitive, ToPrimitive};
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use std::iter::Sum;
 use std::ops::{Add, Sub, Mul, Div, Rem, Neg,
                AddAssign, SubAssign, MulAssign, DivAssign, RemAssign};
+use std::time::Duration;
 use super::calc;
+use super::duration::{duration_from_secs, DurationError};
 use super::{
     Bars,
     Beats,
@@ -18,12 +23,17 @@ use super::{
     Samples,
     Ticks,
     TimeSig,
+    SECOND_IN_MS,
     samples_from_ms,
     ticks_from_ms,
 };
 
 /// Time representation in the form of Milliseconds.
-#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+///
+/// `Ms` has a total ordering following `f64::total_cmp`, so it may be sorted, deduplicated and used
+/// as a key in `BTreeMap` or `HashMap`. `-0.0` is considered equal to `0.0` and every `NaN` is
+/// considered equal to every other `NaN`, ordering after all other values (including infinity).
+#[derive(Debug, Copy, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Ms(pub calc::Ms);
 
@@ -33,16 +43,31 @@ impl Ms {
     #[inline]
     pub fn ms(&self) -> calc::Ms { let Ms(ms) = *self; ms }
 
+    /// The value used for comparison and hashing.
+    ///
+    /// `-0.0` becomes `0.0` and every `NaN` becomes the positive quiet `NaN` so that it orders last.
+    #[inline]
+    fn canonical(&self) -> calc::Ms {
+        let ms = self.ms();
+        if ms.is_nan() {
+            calc::Ms::NAN
+        } else if ms == 0.0 {
+            0.0
+        } else {
+            ms
+        }
+    }
+
     /// Convert to the equivalent duration in Bars.
     #[inline]
-    pub fn bars(&self, bpm: Bpm, ts: TimeSig) -> f64 {
-        self.ms() as f64 / Bars(1).ms(bpm, ts) as f64
+    pub fn bars(&self, bpm: impl Into<Bpm>, ts: TimeSig) -> f64 {
+        self.ms() / Bars(1).ms(bpm, ts)
     }
 
     /// Convert to the equivalent duration in Beats.
     #[inline]
-    pub fn beats(&self, bpm: Bpm) -> f64 {
-        self.ms() as f64 / Beats(1).ms(bpm) as f64
+    pub fn beats(&self, bpm: impl Into<Bpm>) -> f64 {
+        self.ms() / Beats(1).ms(bpm)
     }
 
     /// Convert to unit value of `Samples`.
@@ -58,15 +83,23 @@ impl Ms {
 
     /// Convert to unit value of `Ticks`.
     #[inline]
-    pub fn ticks(&self, bpm: Bpm, ppqn: Ppqn) -> calc::Ticks {
+    pub fn ticks(&self, bpm: impl Into<Bpm>, ppqn: Ppqn) -> calc::Ticks {
         ticks_from_ms(self.ms(), bpm, ppqn)
     }
     /// Convert to `Ticks`.
     #[inline]
-    pub fn to_ticks(&self, bpm: Bpm, ppqn: Ppqn) -> Ticks {
+    pub fn to_ticks(&self, bpm: impl Into<Bpm>, ppqn: Ppqn) -> Ticks {
         Ticks(self.ticks(bpm, ppqn))
     }
 
+    /// Convert to a `Duration`.
+    ///
+    /// Fails if the value is negative, not finite or too large for a `Duration`.
+    #[inline]
+    pub fn to_duration(&self) -> Result<Duration, DurationError> {
+        duration_from_secs(self.ms() / SECOND_IN_MS)
+    }
+
 }
 
 impl From<calc::Ms> for Ms {
@@ -76,6 +109,50 @@ impl From<calc::Ms> for Ms {
     }
 }
 
+impl From<Duration> for Ms {
+    #[inline]
+    fn from(duration: Duration) -> Self {
+        Ms(duration.as_secs_f64() * SECOND_IN_MS)
+    }
+}
+
+impl TryFrom<Ms> for Duration {
+    type Error = DurationError;
+    #[inline]
+    fn try_from(ms: Ms) -> Result<Self, Self::Error> {
+        ms.to_duration()
+    }
+}
+
+impl PartialEq for Ms {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Ms {}
+
+impl PartialOrd for Ms {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Ms {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.canonical().total_cmp(&other.canonical())
+    }
+}
+
+impl Hash for Ms {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.canonical().to_bits().hash(state);
+    }
+}
+
 impl Add for Ms {
     type Output = Self;
     #[inline]
@@ -92,19 +169,35 @@ impl Sub for Ms {
     }
 }
 
-impl Mul for Ms {
+impl Mul<f64> for Ms {
     type Output = Self;
     #[inline]
-    fn mul(self, rhs: Self) -> Self {
-        Self(self.ms() * rhs.ms())
+    fn mul(self, rhs: f64) -> Self {
+        Self(self.ms() * rhs)
     }
 }
 
-impl Div for Ms {
+impl Mul<Ms> for f64 {
+    type Output = Ms;
+    #[inline]
+    fn mul(self, rhs: Ms) -> Ms {
+        Ms(self * rhs.ms())
+    }
+}
+
+impl Div<f64> for Ms {
     type Output = Self;
     #[inline]
-    fn div(self, rhs: Self) -> Self {
-        Self(self.ms() / rhs.ms())
+    fn div(self, rhs: f64) -> Self {
+        Self(self.ms() / rhs)
+    }
+}
+
+impl Div for Ms {
+    type Output = f64;
+    #[inline]
+    fn div(self, rhs: Self) -> f64 {
+        self.ms() / rhs.ms()
     }
 }
 
@@ -136,14 +229,14 @@ impl SubAssign for Ms {
     }
 }
 
-impl MulAssign for Ms {
-    fn mul_assign(&mut self, rhs: Self) {
+impl MulAssign<f64> for Ms {
+    fn mul_assign(&mut self, rhs: f64) {
         *self = *self * rhs;
     }
 }
 
-impl DivAssign for Ms {
-    fn div_assign(&mut self, rhs: Self) {
+impl DivAssign<f64> for Ms {
+    fn div_assign(&mut self, rhs: f64) {
         *self = *self / rhs;
     }
 }
@@ -154,6 +247,18 @@ impl RemAssign for Ms {
     }
 }
 
+impl Sum for Ms {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Ms(0.0), |total, x| total + x)
+    }
+}
+
+impl<'a> Sum<&'a Ms> for Ms {
+    fn sum<I: Iterator<Item = &'a Ms>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
 impl ToPrimitive for Ms {
     fn to_u64(&self) -> Option<u64> {
         self.ms().to_u64()
@@ -0,0 +1,329 @@
+//! Time representation in the form of integer Nanoseconds.
+
+use num::{FromPrimitive, ToPrimitive};
+use std::iter::Sum;
+use std::ops::{Add, Sub, Mul, Div, Rem, Neg,
+               AddAssign, SubAssign, MulAssign, DivAssign, RemAssign};
+use std::time::Duration;
+use crate::calc::{self, RateRatio};
+use crate::duration::{duration_from_nanos, DurationError};
+use crate::{
+    Bpm,
+    Ms,
+    Ppqn,
+    Rounding,
+    SampleHz,
+    Samples,
+    Seconds,
+    Ticks,
+    TimeSig,
+    MINUTE_IN_MS,
+    MS_IN_NS,
+    SECOND_IN_NS,
+};
+
+/// Time representation in the form of integer Nanoseconds.
+///
+/// Unlike `Ms`, `Nanos` never loses precision as it grows, is `Eq`, `Ord` and `Hash` (so it may be
+/// used as a map key) and converts exactly to `Samples` at whole number sample rates and to
+/// `Ticks` at whole number tempos. This makes it the recommended representation of absolute time
+/// for long-running sessions: an `i64` covers roughly 292 years at nanosecond resolution.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Nanos(pub calc::Nanos);
+
+impl Nanos {
+
+    /// Return the unit value of Nanos.
+    #[inline]
+    pub fn nanos(&self) -> calc::Nanos { let Nanos(nanos) = *self; nanos }
+
+    /// Convert to the equivalent duration in Bars.
+    #[inline]
+    pub fn bars(&self, bpm: impl Into<Bpm>, ts: TimeSig) -> f64 {
+        self.to_ms().bars(bpm, ts)
+    }
+
+    /// Convert to the equivalent duration in Beats.
+    #[inline]
+    pub fn beats(&self, bpm: impl Into<Bpm>) -> f64 {
+        self.to_ms().beats(bpm)
+    }
+
+    /// Convert to the unit value of `Ms`.
+    #[inline]
+    pub fn ms(&self) -> calc::Ms {
+        self.nanos() as calc::Ms / MS_IN_NS as calc::Ms
+    }
+    /// Convert to `Ms`.
+    #[inline]
+    pub fn to_ms(&self) -> Ms {
+        Ms(self.ms())
+    }
+
+    /// Convert to the unit value of `Seconds`.
+    #[inline]
+    pub fn seconds(&self) -> calc::Seconds {
+        self.nanos() as calc::Seconds / SECOND_IN_NS as calc::Seconds
+    }
+    /// Convert to `Seconds`.
+    #[inline]
+    pub fn to_seconds(&self) -> Seconds {
+        Seconds(self.seconds())
+    }
+
+    /// Convert to the unit value of `Samples`, truncating towards zero.
+    #[inline]
+    pub fn samples(&self, sample_hz: SampleHz) -> calc::Samples {
+        self.to_samples_rounded(sample_hz, Rounding::Truncate).samples()
+    }
+    /// Convert to `Samples`, truncating towards zero.
+    #[inline]
+    pub fn to_samples(&self, sample_hz: SampleHz) -> Samples {
+        Samples(self.samples(sample_hz))
+    }
+    /// Convert to `Samples` using the given rounding.
+    ///
+    /// Whole number sample rates are converted with exact integer math.
+    #[inline]
+    pub fn to_samples_rounded(&self, sample_hz: SampleHz, rounding: Rounding) -> Samples {
+        Samples(RateRatio::new(SECOND_IN_NS as f64, sample_hz).apply(self.nanos(), rounding))
+    }
+    /// Convert from `Samples` using the given rounding.
+    ///
+    /// Whole number sample rates are converted with exact integer math.
+    #[inline]
+    pub fn from_samples(samples: Samples, sample_hz: SampleHz, rounding: Rounding) -> Nanos {
+        Nanos(RateRatio::new(sample_hz, SECOND_IN_NS as f64).apply(samples.samples(), rounding))
+    }
+
+    /// Convert to the unit value of `Ticks`, rounding to the nearest tick.
+    #[inline]
+    pub fn ticks(&self, bpm: impl Into<Bpm>, ppqn: Ppqn) -> calc::Ticks {
+        self.to_ticks_rounded(bpm, ppqn, Rounding::Nearest).ticks()
+    }
+    /// Convert to `Ticks`, rounding to the nearest tick.
+    #[inline]
+    pub fn to_ticks(&self, bpm: impl Into<Bpm>, ppqn: Ppqn) -> Ticks {
+        Ticks(self.ticks(bpm, ppqn))
+    }
+    /// Convert to `Ticks` using the given rounding.
+    ///
+    /// Whole number tempos are converted with exact integer math.
+    #[inline]
+    pub fn to_ticks_rounded(&self, bpm: impl Into<Bpm>, ppqn: Ppqn, rounding: Rounding) -> Ticks {
+        let (minute_in_ns, ticks_per_minute) = minute_in_ns_and_ticks(bpm.into(), ppqn);
+        Ticks(RateRatio::new(minute_in_ns, ticks_per_minute).apply(self.nanos(), rounding))
+    }
+    /// Convert from `Ticks` using the given rounding.
+    ///
+    /// Whole number tempos are converted with exact integer math.
+    #[inline]
+    pub fn from_ticks(ticks: Ticks, bpm: impl Into<Bpm>, ppqn: Ppqn, rounding: Rounding) -> Nanos {
+        let (minute_in_ns, ticks_per_minute) = minute_in_ns_and_ticks(bpm.into(), ppqn);
+        Nanos(RateRatio::new(ticks_per_minute, minute_in_ns).apply(ticks.ticks(), rounding))
+    }
+
+    /// Convert to a `Duration`.
+    ///
+    /// Fails if the value is negative.
+    #[inline]
+    pub fn to_duration(&self) -> Result<Duration, DurationError> {
+        duration_from_nanos(self.nanos() as i128)
+    }
+
+    /// Checked addition. Returns `None` if overflow occurred.
+    #[inline]
+    pub fn checked_add(&self, rhs: Self) -> Option<Self> {
+        self.nanos().checked_add(rhs.nanos()).map(Self)
+    }
+    /// Checked subtraction. Returns `None` if overflow occurred.
+    #[inline]
+    pub fn checked_sub(&self, rhs: Self) -> Option<Self> {
+        self.nanos().checked_sub(rhs.nanos()).map(Self)
+    }
+    /// Checked multiplication by a scalar. Returns `None` if overflow occurred.
+    #[inline]
+    pub fn checked_mul(&self, rhs: calc::Nanos) -> Option<Self> {
+        self.nanos().checked_mul(rhs).map(Self)
+    }
+    /// Checked division by a scalar. Returns `None` if `rhs == 0` or overflow occurred.
+    #[inline]
+    pub fn checked_div(&self, rhs: calc::Nanos) -> Option<Self> {
+        self.nanos().checked_div(rhs).map(Self)
+    }
+
+    /// Saturating addition. Clamps the result at the numeric bounds instead of overflowing.
+    #[inline]
+    pub fn saturating_add(&self, rhs: Self) -> Self {
+        Self(self.nanos().saturating_add(rhs.nanos()))
+    }
+    /// Saturating subtraction. Clamps the result at the numeric bounds instead of overflowing.
+    #[inline]
+    pub fn saturating_sub(&self, rhs: Self) -> Self {
+        Self(self.nanos().saturating_sub(rhs.nanos()))
+    }
+    /// Saturating multiplication by a scalar. Clamps the result at the numeric bounds instead of
+    /// overflowing.
+    #[inline]
+    pub fn saturating_mul(&self, rhs: calc::Nanos) -> Self {
+        Self(self.nanos().saturating_mul(rhs))
+    }
+
+}
+
+/// The number of nanoseconds in a minute along with the number of ticks per minute.
+#[inline]
+fn minute_in_ns_and_ticks(bpm: Bpm, ppqn: Ppqn) -> (f64, f64) {
+    (MINUTE_IN_MS * MS_IN_NS as f64, bpm * ppqn as f64)
+}
+
+impl From<calc::Nanos> for Nanos {
+    #[inline]
+    fn from(nanos: calc::Nanos) -> Self {
+        Nanos(nanos)
+    }
+}
+
+impl TryFrom<Duration> for Nanos {
+    type Error = DurationError;
+    /// Fails if the `Duration` exceeds `i64::MAX` nanoseconds (roughly 292 years).
+    #[inline]
+    fn try_from(duration: Duration) -> Result<Self, Self::Error> {
+        calc::Nanos::try_from(duration.as_nanos())
+            .map(Nanos)
+            .map_err(|_| DurationError::Overflow)
+    }
+}
+
+impl TryFrom<Nanos> for Duration {
+    type Error = DurationError;
+    #[inline]
+    fn try_from(nanos: Nanos) -> Result<Self, Self::Error> {
+        nanos.to_duration()
+    }
+}
+
+impl Add for Nanos {
+    type Output = Self;
+    #[inline]
+    fn add(self, rhs: Self) -> Nanos {
+        Nanos(self.nanos() + rhs.nanos())
+    }
+}
+
+impl Sub for Nanos {
+    type Output = Self;
+    #[inline]
+    fn sub(self, rhs: Self) -> Nanos {
+        Nanos(self.nanos() - rhs.nanos())
+    }
+}
+
+impl Mul<calc::Nanos> for Nanos {
+    type Output = Self;
+    #[inline]
+    fn mul(self, rhs: calc::Nanos) -> Self {
+        Self(self.nanos() * rhs)
+    }
+}
+
+impl Mul<Nanos> for calc::Nanos {
+    type Output = Nanos;
+    #[inline]
+    fn mul(self, rhs: Nanos) -> Nanos {
+        Nanos(self * rhs.nanos())
+    }
+}
+
+impl Div<calc::Nanos> for Nanos {
+    type Output = Self;
+    #[inline]
+    fn div(self, rhs: calc::Nanos) -> Self {
+        Self(self.nanos() / rhs)
+    }
+}
+
+impl Div for Nanos {
+    type Output = f64;
+    #[inline]
+    fn div(self, rhs: Self) -> f64 {
+        self.nanos() as f64 / rhs.nanos() as f64
+    }
+}
+
+impl Rem for Nanos {
+    type Output = Self;
+    #[inline]
+    fn rem(self, rhs: Self) -> Nanos {
+        Nanos(self.nanos() % rhs.nanos())
+    }
+}
+
+impl Neg for Nanos {
+    type Output = Self;
+    #[inline]
+    fn neg(self) -> Nanos {
+        Nanos(-self.nanos())
+    }
+}
+
+impl AddAssign for Nanos {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Nanos {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign<calc::Nanos> for Nanos {
+    fn mul_assign(&mut self, rhs: calc::Nanos) {
+        *self = *self * rhs;
+    }
+}
+
+impl DivAssign<calc::Nanos> for Nanos {
+    fn div_assign(&mut self, rhs: calc::Nanos) {
+        *self = *self / rhs;
+    }
+}
+
+impl RemAssign for Nanos {
+    fn rem_assign(&mut self, rhs: Self) {
+        *self = *self % rhs;
+    }
+}
+
+impl Sum for Nanos {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Nanos(0), |total, x| total + x)
+    }
+}
+
+impl<'a> Sum<&'a Nanos> for Nanos {
+    fn sum<I: Iterator<Item = &'a Nanos>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+impl ToPrimitive for Nanos {
+    fn to_u64(&self) -> Option<u64> {
+        self.nanos().to_u64()
+    }
+    fn to_i64(&self) -> Option<i64> {
+        self.nanos().to_i64()
+    }
+}
+
+impl FromPrimitive for Nanos {
+    fn from_u64(n: u64) -> Option<Nanos> {
+        Some(Nanos(n as calc::Nanos))
+    }
+    fn from_i64(n: i64) -> Option<Nanos> {
+        Some(Nanos(n as calc::Nanos))
+    }
+}
@@ -0,0 +1,91 @@
+//! Tempo-synced phase for LFOs and other modulators.
+//!
+//! Phase is derived from the absolute musical position, so a modulator stays locked to the bar
+//! after seeking or looping.
+
+use crate::{
+    Measure,
+    Ppqn,
+    SampleHz,
+    Samples,
+    TempoMap,
+    Ticks,
+    TimeSig,
+    ms_from_samples,
+};
+
+/// The normalized phase in `[0, 1)` at the given position in beats (quarter notes) of a modulator
+/// whose cycle lasts `period`.
+///
+/// `offset` is added to the phase as a fraction of a cycle.
+#[inline]
+pub fn phase_at_beats(beats: f64, period: Measure, ts: TimeSig, offset: f64) -> f64 {
+    wrap_phase(beats / period.beats(ts) + offset)
+}
+
+/// The normalized phase in `[0, 1)` at the given position in `Ticks` of a modulator whose cycle
+/// lasts `period`.
+///
+/// `offset` is added to the phase as a fraction of a cycle.
+#[inline]
+pub fn phase_at_ticks(ticks: Ticks, ppqn: Ppqn, period: Measure, ts: TimeSig, offset: f64) -> f64 {
+    phase_at_beats(ticks.beats(ppqn), period, ts, offset)
+}
+
+/// Fill `buffer` with the phase at each sample of an audio block beginning at `start`.
+///
+/// The musical position of each sample is read from `map`, so tempo changes that occur within the
+/// block are followed exactly.
+pub fn fill_phase(buffer: &mut [f64], start: Samples, sample_hz: SampleHz, map: &TempoMap,
+                  period: Measure, ts: TimeSig, offset: f64) {
+    let period_beats = period.beats(ts);
+    let start_ms = ms_from_samples(start.samples(), sample_hz);
+    let mut index = map.index_at_ms(start_ms);
+    let num_changes = map.changes().len();
+    for (i, phase) in buffer.iter_mut().enumerate() {
+        let ms = ms_from_samples(start.samples() + i as i64, sample_hz);
+        while index + 1 < num_changes && map.start_ms(index + 1) <= ms {
+            index += 1;
+        }
+        let beats = map.beats_in_segment(index, ms);
+        *phase = wrap_phase(beats / period_beats + offset);
+    }
+}
+
+/// Wrap a number of cycles to a phase in `[0, 1)`.
+///
+/// `rem_euclid` rounds tiny negative values up to exactly `1.0`, which is the start of the next
+/// cycle and so is mapped to `0.0`.
+#[inline]
+pub(crate) fn wrap_phase(cycles: f64) -> f64 {
+    let phase = cycles.rem_euclid(1.0);
+    if phase == 1.0 { 0.0 } else { phase }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Division, DivType};
+
+    #[test]
+    fn wrap_phase_range() {
+        assert_eq!(wrap_phase(-1e-17), 0.0);
+        assert_eq!(wrap_phase(-0.25), 0.75);
+        assert_eq!(wrap_phase(2.5), 0.5);
+        assert_eq!(wrap_phase(1.0), 0.0);
+    }
+
+    #[test]
+    fn phase_stays_below_one() {
+        let ts = TimeSig { top: 4, bottom: 4 };
+        let bar = Measure(1, Division::Bar, DivType::Whole);
+        assert_eq!(phase_at_beats(-1e-16, bar, ts, 0.0), 0.0);
+        assert_eq!(phase_at_beats(0.0, bar, ts, -1e-17), 0.0);
+        assert_eq!(phase_at_beats(6.0, bar, ts, 0.0), 0.5);
+
+        let map = TempoMap::new(120.0, 960).unwrap();
+        let mut buffer = [1.0; 4];
+        fill_phase(&mut buffer, Samples(0), 48_000.0, &map, bar, ts, -1e-17);
+        assert!(buffer.iter().all(|&phase| (0.0..1.0).contains(&phase)));
+    }
+}
@@ -0,0 +1,121 @@
+//! Remapping existing positions after a `TempoMap` is edited.
+
+use crate::calc;
+use crate::{
+    Ms,
+    Rounding,
+    SampleHz,
+    Samples,
+    TempoMap,
+    Ticks,
+    SECOND_IN_MS,
+};
+
+/// The time domain in which a position is fixed when the tempo changes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TimeDomain {
+    /// The position keeps its `Ticks`, moving in time with the tempo, i.e. MIDI.
+    Musical,
+    /// The position keeps its time, moving in `Ticks`, i.e. audio.
+    Absolute,
+}
+
+/// A musical position along with the domain in which it is fixed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Position {
+    pub ticks: Ticks,
+    pub domain: TimeDomain,
+}
+
+/// The tempo map before and after an edit, used to remap positions from the old map to the new.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TempoRemap {
+    pub old: TempoMap,
+    pub new: TempoMap,
+}
+
+impl Position {
+
+    /// A position that keeps its `Ticks` when the tempo changes.
+    #[inline]
+    pub fn musical(ticks: Ticks) -> Position {
+        Position { ticks, domain: TimeDomain::Musical }
+    }
+
+    /// A position that keeps its time when the tempo changes.
+    #[inline]
+    pub fn absolute(ticks: Ticks) -> Position {
+        Position { ticks, domain: TimeDomain::Absolute }
+    }
+
+}
+
+impl TempoRemap {
+
+    /// Remap a position according to its domain.
+    #[inline]
+    pub fn remap(&self, position: Position) -> Position {
+        Position { ticks: self.ticks(position.ticks, position.domain), ..position }
+    }
+
+    /// Remap a position in `Ticks`, preserving either its `Ticks` or its time.
+    #[inline]
+    pub fn ticks(&self, ticks: Ticks, domain: TimeDomain) -> Ticks {
+        match domain {
+            TimeDomain::Musical => ticks,
+            TimeDomain::Absolute => self.new.to_ticks(self.old.to_ms(ticks)),
+        }
+    }
+
+    /// Remap a time in `Ms`, preserving either its `Ticks` or its time.
+    #[inline]
+    pub fn ms(&self, ms: Ms, domain: TimeDomain) -> Ms {
+        match domain {
+            TimeDomain::Musical => Ms(self.new.ms_at_beats(self.old.beats_at_ms(ms.ms()))),
+            TimeDomain::Absolute => ms,
+        }
+    }
+
+    /// Remap a time in `Samples`, preserving either its `Ticks` or its time.
+    ///
+    /// Musical positions are rounded to a sample using the given rounding, while absolute
+    /// positions are returned unchanged.
+    #[inline]
+    pub fn samples(&self, samples: Samples, sample_hz: SampleHz, domain: TimeDomain,
+                   rounding: Rounding) -> Samples {
+        match domain {
+            TimeDomain::Musical => {
+                let ms = self.ms(samples.to_ms(sample_hz), domain).ms();
+                Samples(rounding.round(ms * sample_hz / SECOND_IN_MS) as calc::Samples)
+            },
+            TimeDomain::Absolute => samples,
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn samples_follow_rounding() {
+        let old = TempoMap::new(120.0, 960).unwrap();
+        let mut new = old.clone();
+        new.insert(Ticks(0), 90.0).unwrap();
+        let remap = TempoRemap { old: old.clone(), new };
+        // 1 sample at 120 BPM lasts 4/3 samples at 90 BPM.
+        let musical = |rounding| remap.samples(Samples(1), 48_000.0, TimeDomain::Musical, rounding);
+        assert_eq!(musical(Rounding::Truncate), Samples(1));
+        assert_eq!(musical(Rounding::Ceil), Samples(2));
+        let absolute = remap.samples(Samples(1), 48_000.0, TimeDomain::Absolute, Rounding::Ceil);
+        assert_eq!(absolute, Samples(1));
+
+        let identity = TempoRemap { old: old.clone(), new: old };
+        assert!((0..10_000).all(|s| {
+            identity.samples(Samples(s), 44_100.0, TimeDomain::Musical, Rounding::Nearest) == Samples(s)
+        }));
+    }
+}
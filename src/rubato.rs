@@ -0,0 +1,270 @@
+//! Building a tempo map that follows a freely played performance from its beat times.
+
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use crate::calc;
+use crate::{
+    Bpm,
+    Ms,
+    Ppqn,
+    SampleHz,
+    Samples,
+    TempoMap,
+    TempoMapError,
+    Ticks,
+    TimeSig,
+    MINUTE_IN_MS,
+    ms_from_samples,
+};
+
+/// The shape of the tempo between recorded beats.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RubatoCurve {
+    /// A single tempo change at each beat.
+    PerBeat,
+    /// Each beat is divided into the given number of steps whose tempos follow a line between the
+    /// tempos of neighbouring beats, scaled so that every beat still lands on its timestamp.
+    Smoothed { steps: u32 },
+}
+
+/// Builds a `TempoMap` from the times at which each beat of a performance occurred.
+///
+/// Beats are the beats of the meter, i.e. `4 / ts.bottom` quarter notes, and are placed on
+/// consecutive beats of the bar so that bar lines follow the performance.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RubatoBuilder {
+    pub ts: TimeSig,
+    pub ppqn: Ppqn,
+    pub curve: RubatoCurve,
+    /// The beat of the bar on which the first recorded beat falls, where `0` is the downbeat.
+    pub first_beat_in_bar: u16,
+    /// The position of the first recorded beat. If `None`, a position on `first_beat_in_bar` is
+    /// chosen so that the lead-in from `Ticks(0)` is as near as possible to the first beat's tempo.
+    pub first_beat: Option<Ticks>,
+}
+
+/// A `TempoMap` built by a `RubatoBuilder`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RubatoMap {
+    pub map: TempoMap,
+    /// The position of each recorded beat within the map.
+    pub beats: Vec<Ticks>,
+    /// The greatest distance between a recorded beat's timestamp and its position converted back
+    /// through the map.
+    pub max_error: Ms,
+}
+
+/// The reasons a `RubatoBuilder` may fail to build a map.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum RubatoError {
+    /// At least two beats are required to determine a tempo.
+    TooFewBeats,
+    /// Each beat must occur strictly after the one before it.
+    NotIncreasing,
+    /// Beats must not occur before the start of the map.
+    NegativeTime,
+    /// The first beat must be at `Ticks(0)` if and only if it occurs at time zero, as the map has
+    /// no room for a lead-in otherwise.
+    NoLeadIn,
+    /// The beat times produce a tempo that is not positive and finite.
+    InvalidTempo,
+    /// The time signature's `bottom` is `0`.
+    InvalidMeter,
+    /// The resolution is `0`.
+    InvalidPpqn,
+}
+
+impl fmt::Display for RubatoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match *self {
+            RubatoError::TooFewBeats => "at least two beats are required",
+            RubatoError::NotIncreasing => "beat times must be strictly increasing",
+            RubatoError::NegativeTime => "beat times must not be negative",
+            RubatoError::NoLeadIn => "the first beat leaves no room for a lead-in",
+            RubatoError::InvalidTempo => "beat times produce a tempo that is not positive and finite",
+            RubatoError::InvalidMeter => "the time signature must have a non-zero bottom",
+            RubatoError::InvalidPpqn => "ppqn must be greater than 0",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl Error for RubatoError {}
+
+impl RubatoBuilder {
+
+    /// Construct a builder for the given meter and resolution.
+    ///
+    /// By default there is a tempo change at each beat and the first beat falls on a downbeat.
+    pub fn new(ts: TimeSig, ppqn: Ppqn) -> RubatoBuilder {
+        RubatoBuilder {
+            ts,
+            ppqn,
+            curve: RubatoCurve::PerBeat,
+            first_beat_in_bar: 0,
+            first_beat: None,
+        }
+    }
+
+    /// Build a map from beat times in `Samples`.
+    pub fn build_samples(&self, beats: &[Samples], sample_hz: SampleHz) -> Result<RubatoMap, RubatoError> {
+        let beats: Vec<Ms> = beats.iter()
+            .map(|samples| Ms(ms_from_samples(samples.samples(), sample_hz)))
+            .collect();
+        self.build_ms(&beats)
+    }
+
+    /// Build a map from beat times in `Ms`.
+    pub fn build_ms(&self, beats: &[Ms]) -> Result<RubatoMap, RubatoError> {
+        if self.ts.bottom == 0 {
+            return Err(RubatoError::InvalidMeter);
+        }
+        if self.ppqn == 0 {
+            return Err(RubatoError::InvalidPpqn);
+        }
+        if beats.len() < 2 {
+            return Err(RubatoError::TooFewBeats);
+        }
+        if beats[0].ms() < 0.0 {
+            return Err(RubatoError::NegativeTime);
+        }
+        if beats.windows(2).any(|pair| pair[0].ms().partial_cmp(&pair[1].ms()) != Some(Ordering::Less)) {
+            return Err(RubatoError::NotIncreasing);
+        }
+        let first = self.first_beat_ticks(beats)?;
+        let ticks: Vec<Ticks> = (0..beats.len() as i64)
+            .map(|i| first + self.beat_to_ticks(i))
+            .collect();
+        let per_beat: Vec<Bpm> = ticks.windows(2).zip(beats.windows(2))
+            .map(|(t, ms)| bpm_between(t[0], t[1], ms[1].ms() - ms[0].ms(), self.ppqn))
+            .collect();
+
+        let mut map = TempoMap::new(per_beat[0], self.ppqn).map_err(|_| RubatoError::InvalidTempo)?;
+        if first.ticks() > 0 {
+            let bpm = bpm_between(Ticks(0), first, beats[0].ms(), self.ppqn);
+            map.insert(Ticks(0), bpm).map_err(|_| RubatoError::InvalidTempo)?;
+        }
+        match self.curve {
+            RubatoCurve::PerBeat => {
+                for (i, &bpm) in per_beat.iter().enumerate() {
+                    map.insert(ticks[i], bpm).map_err(|_| RubatoError::InvalidTempo)?;
+                }
+            },
+            RubatoCurve::Smoothed { steps } => {
+                for i in 0..per_beat.len() {
+                    // The tempo at each end of the beat is the mean of the neighbouring beats.
+                    let start = (per_beat[i.saturating_sub(1)] + per_beat[i]) / 2.0;
+                    let end = (per_beat[i] + per_beat[(i + 1).min(per_beat.len() - 1)]) / 2.0;
+                    let duration = beats[i + 1].ms() - beats[i].ms();
+                    insert_smoothed(&mut map, ticks[i], ticks[i + 1], duration, start, end, steps)
+                        .map_err(|_| RubatoError::InvalidTempo)?;
+                }
+            },
+        }
+        // Continue at the tempo of the final beat.
+        let last = ticks.len() - 1;
+        map.insert(ticks[last], per_beat[last - 1]).map_err(|_| RubatoError::InvalidTempo)?;
+
+        let max_error = ticks.iter().zip(beats)
+            .map(|(&t, ms)| (map.to_ms(t) - *ms).ms().abs())
+            .fold(0.0, f64::max);
+        Ok(RubatoMap { map, beats: ticks, max_error: Ms(max_error) })
+    }
+
+    /// The position of the first recorded beat.
+    fn first_beat_ticks(&self, beats: &[Ms]) -> Result<Ticks, RubatoError> {
+        let lead_in = beats[0].ms();
+        let first = match self.first_beat {
+            Some(first) => first,
+            None => {
+                // Of the beats on `first_beat_in_bar` either side of the number of beats that the
+                // lead-in lasts at the first recorded beat's tempo, take the nearer as a ratio.
+                let top = self.ts.top.max(1) as i64;
+                let lead_in_beats = lead_in / (beats[1].ms() - beats[0].ms());
+                let offset = self.first_beat_in_bar as i64 % top;
+                let bars = ((lead_in_beats - offset as f64) / top as f64).floor().max(0.0) as i64;
+                let index = [bars * top + offset, (bars + 1) * top + offset].into_iter()
+                    .filter(|&index| (index == 0) == (lead_in == 0.0))
+                    .min_by(|&a, &b| {
+                        let distance = |index: i64| (index as f64 / lead_in_beats).ln().abs();
+                        distance(a).total_cmp(&distance(b))
+                    })
+                    .unwrap_or(offset);
+                self.beat_to_ticks(index)
+            },
+        };
+        if first.ticks() < 0 || (first.ticks() == 0) != (lead_in == 0.0) {
+            return Err(RubatoError::NoLeadIn);
+        }
+        Ok(first)
+    }
+
+    /// The position of the beat of the meter at the given index, rounded to avoid drift.
+    #[inline]
+    fn beat_to_ticks(&self, index: i64) -> Ticks {
+        let num = index as i128 * 4 * self.ppqn as i128;
+        Ticks(calc::Rounding::Nearest.div(num, self.ts.bottom as i128) as calc::Ticks)
+    }
+
+}
+
+/// The tempo at which the span between two positions lasts `ms`.
+#[inline]
+fn bpm_between(start: Ticks, end: Ticks, ms: calc::Ms, ppqn: Ppqn) -> Bpm {
+    (end - start).beats(ppqn) * MINUTE_IN_MS / ms
+}
+
+/// Insert steps between `start` and `end` whose tempos follow a line from `start_bpm` to
+/// `end_bpm`, scaled so that the span lasts `duration` milliseconds.
+fn insert_smoothed(map: &mut TempoMap, start: Ticks, end: Ticks, duration: calc::Ms,
+                   start_bpm: Bpm, end_bpm: Bpm, steps: u32) -> Result<(), TempoMapError> {
+    let span = (end - start).ticks();
+    let steps = (steps as calc::Ticks).clamp(1, span.max(1));
+    let step_ticks = |j: calc::Ticks| start + Ticks(span * j / steps);
+    let step_bpm = |j: calc::Ticks| {
+        let t = (j as f64 + 0.5) / steps as f64;
+        start_bpm + (end_bpm - start_bpm) * t
+    };
+    let unscaled: calc::Ms = (0..steps)
+        .map(|j| (step_ticks(j + 1) - step_ticks(j)).beats(map.ppqn()) * MINUTE_IN_MS / step_bpm(j))
+        .sum();
+    let scale = unscaled / duration;
+    for j in 0..steps {
+        map.insert(step_ticks(j), step_bpm(j) * scale)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BEATS: [Ms; 3] = [Ms(0.0), Ms(500.0), Ms(1_000.0)];
+
+    #[test]
+    fn rejects_invalid_meter_and_resolution() {
+        let builder = RubatoBuilder::new(TimeSig { top: 4, bottom: 0 }, 960);
+        assert_eq!(builder.build_ms(&BEATS), Err(RubatoError::InvalidMeter));
+        let builder = RubatoBuilder::new(TimeSig { top: 4, bottom: 4 }, 0);
+        assert_eq!(builder.build_ms(&BEATS), Err(RubatoError::InvalidPpqn));
+    }
+
+    #[test]
+    fn rejects_infinite_beats() {
+        let builder = RubatoBuilder::new(TimeSig { top: 4, bottom: 4 }, 960);
+        let beats = [Ms(0.0), Ms(f64::INFINITY)];
+        assert_eq!(builder.build_ms(&beats), Err(RubatoError::InvalidTempo));
+    }
+
+    #[test]
+    fn beats_land_on_their_times() {
+        let builder = RubatoBuilder::new(TimeSig { top: 4, bottom: 4 }, 960);
+        let map = builder.build_ms(&BEATS).unwrap();
+        assert_eq!(map.beats, vec![Ticks(0), Ticks(960), Ticks(1_920)]);
+        assert_eq!(map.map.bpm_at(Ticks(0)), 120.0);
+        assert!(map.max_error.ms() < 1e-9);
+    }
+}
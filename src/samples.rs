@@ -6,18 +6,23 @@
 //!
 
 use num::{FromPrimitive, ToPrimitive};
+use std::iter::Sum;
 use std::ops::{Add, Sub, Mul, Div, Rem, Neg,
                AddAssign, SubAssign, MulAssign, DivAssign, RemAssign};
-use super::calc;
+use std::time::Duration;
+use super::calc::{self, RateRatio, SECOND_IN_NS};
+use super::duration::{check_sample_hz, duration_from_nanos, DurationError};
 use super::{
     Bars,
     Beats,
     Bpm,
     Ms,
     Ppqn,
+    Rounding,
     SampleHz,
     Ticks,
     ms_from_samples,
+    samples_from_sample_hz,
     ticks_from_samples,
     TimeSig,
 };
@@ -35,13 +40,13 @@ impl Samples {
 
     /// Convert to the equivalent duration in Bars.
     #[inline]
-    pub fn bars(&self, bpm: Bpm, ts: TimeSig, sample_hz: SampleHz) -> f64 {
+    pub fn bars(&self, bpm: impl Into<Bpm>, ts: TimeSig, sample_hz: SampleHz) -> f64 {
         self.samples() as f64 / Bars(1).samples(bpm, ts, sample_hz) as f64
     }
 
     /// Convert to the equivalent duration in Beats.
     #[inline]
-    pub fn beats(&self, bpm: Bpm, sample_hz: SampleHz) -> f64 {
+    pub fn beats(&self, bpm: impl Into<Bpm>, sample_hz: SampleHz) -> f64 {
         self.samples() as f64 / Beats(1).samples(bpm, sample_hz) as f64
     }
 
@@ -58,15 +63,147 @@ impl Samples {
 
     /// Convert to the unit value of `Ticks`.
     #[inline]
-    pub fn ticks(&self, bpm: Bpm, ppqn: Ppqn, sample_hz: SampleHz) -> calc::Ticks {
+    pub fn ticks(&self, bpm: impl Into<Bpm>, ppqn: Ppqn, sample_hz: SampleHz) -> calc::Ticks {
         ticks_from_samples(self.samples(), bpm, ppqn, sample_hz)
     }
     /// Convert to `Ticks`.
     #[inline]
-    pub fn to_ticks(&self, bpm: Bpm, ppqn: Ppqn, sample_hz: SampleHz) -> Ticks {
+    pub fn to_ticks(&self, bpm: impl Into<Bpm>, ppqn: Ppqn, sample_hz: SampleHz) -> Ticks {
         Ticks(self.ticks(bpm, ppqn, sample_hz))
     }
 
+    /// Convert from a `Duration` at the given sample rate, truncating towards zero.
+    ///
+    /// Whole number sample rates are converted with exact integer math. Durations too long to be
+    /// represented saturate at the maximum number of `Samples`.
+    ///
+    /// Fails if the sample rate is not positive and finite.
+    pub fn from_duration(duration: Duration, sample_hz: SampleHz) -> Result<Samples, DurationError> {
+        check_sample_hz(sample_hz)?;
+        let nanos = duration.as_nanos().min(i128::MAX as u128) as i128;
+        let ratio = RateRatio::new(SECOND_IN_NS as SampleHz, sample_hz);
+        let samples = ratio.apply_i128(nanos, Rounding::Truncate);
+        Ok(Samples(samples.min(calc::Samples::MAX as i128) as calc::Samples))
+    }
+    /// Convert to a `Duration` at the given sample rate, rounded to the nearest nanosecond.
+    ///
+    /// Fails if the sample rate is not positive and finite, the number of samples is negative or
+    /// the result is too large for a `Duration`.
+    pub fn to_duration(&self, sample_hz: SampleHz) -> Result<Duration, DurationError> {
+        check_sample_hz(sample_hz)?;
+        let ratio = RateRatio::new(sample_hz, SECOND_IN_NS as SampleHz);
+        duration_from_nanos(ratio.apply_i128(self.samples() as i128, Rounding::Nearest))
+    }
+
+    /// Convert to the equivalent number of `Samples` at another sample rate.
+    ///
+    /// Whole number sample rates are converted with exact integer ratio math.
+    #[inline]
+    pub fn to_sample_hz(&self, from_hz: SampleHz, to_hz: SampleHz, rounding: Rounding) -> Samples {
+        Samples(samples_from_sample_hz(self.samples(), from_hz, to_hz, rounding))
+    }
+
+    /// Convert every value in the slice to the equivalent number of `Samples` at another sample
+    /// rate in place.
+    ///
+    /// The ratio between the two rates is only reduced once for the whole slice.
+    pub fn slice_to_sample_hz(samples: &mut [Samples], from_hz: SampleHz, to_hz: SampleHz,
+                              rounding: Rounding) {
+        let ratio = RateRatio::new(from_hz, to_hz);
+        for s in samples.iter_mut() {
+            *s = Samples(ratio.apply(s.samples(), rounding));
+        }
+    }
+
+    /// Convert to the equivalent number of `Samples` at the oversampled rate.
+    ///
+    /// Clamps the result at the numeric bounds instead of overflowing.
+    #[inline]
+    pub fn oversample(&self, factor: Oversampling) -> Samples {
+        self.saturating_mul(factor.factor())
+    }
+
+    /// Convert to the equivalent number of `Samples` at the oversampled rate. Returns `None` if
+    /// overflow occurred.
+    #[inline]
+    pub fn checked_oversample(&self, factor: Oversampling) -> Option<Samples> {
+        self.checked_mul(factor.factor())
+    }
+
+    /// Convert from the oversampled rate back to the equivalent number of `Samples` at the base
+    /// rate.
+    #[inline]
+    pub fn downsample(&self, factor: Oversampling, rounding: Rounding) -> Samples {
+        Samples(rounding.div(self.samples() as i128, factor.factor() as i128) as calc::Samples)
+    }
+
+    /// Checked addition. Returns `None` if overflow occurred.
+    #[inline]
+    pub fn checked_add(&self, rhs: Self) -> Option<Self> {
+        self.samples().checked_add(rhs.samples()).map(Self)
+    }
+    /// Checked subtraction. Returns `None` if overflow occurred.
+    #[inline]
+    pub fn checked_sub(&self, rhs: Self) -> Option<Self> {
+        self.samples().checked_sub(rhs.samples()).map(Self)
+    }
+    /// Checked multiplication by a scalar. Returns `None` if overflow occurred.
+    #[inline]
+    pub fn checked_mul(&self, rhs: calc::Samples) -> Option<Self> {
+        self.samples().checked_mul(rhs).map(Self)
+    }
+    /// Checked division by a scalar. Returns `None` if `rhs == 0` or overflow occurred.
+    #[inline]
+    pub fn checked_div(&self, rhs: calc::Samples) -> Option<Self> {
+        self.samples().checked_div(rhs).map(Self)
+    }
+
+    /// Saturating addition. Clamps the result at the numeric bounds instead of overflowing.
+    #[inline]
+    pub fn saturating_add(&self, rhs: Self) -> Self {
+        Self(self.samples().saturating_add(rhs.samples()))
+    }
+    /// Saturating subtraction. Clamps the result at the numeric bounds instead of overflowing.
+    #[inline]
+    pub fn saturating_sub(&self, rhs: Self) -> Self {
+        Self(self.samples().saturating_sub(rhs.samples()))
+    }
+    /// Saturating multiplication by a scalar. Clamps the result at the numeric bounds instead of
+    /// overflowing.
+    #[inline]
+    pub fn saturating_mul(&self, rhs: calc::Samples) -> Self {
+        Self(self.samples().saturating_mul(rhs))
+    }
+
+}
+
+/// The oversampling factors commonly used for DSP.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Oversampling {
+    X2,
+    X4,
+    X8,
+}
+
+impl Oversampling {
+
+    /// The multiplier applied to the base sample rate.
+    #[inline]
+    pub fn factor(&self) -> calc::Samples {
+        match *self {
+            Oversampling::X2 => 2,
+            Oversampling::X4 => 4,
+            Oversampling::X8 => 8,
+        }
+    }
+
+    /// The oversampled rate for the given base sample rate.
+    #[inline]
+    pub fn sample_hz(&self, base_hz: SampleHz) -> SampleHz {
+        base_hz * self.factor() as SampleHz
+    }
+
 }
 
 impl From<calc::Samples> for Samples {
@@ -91,19 +228,35 @@ impl Sub for Samples {
     }
 }
 
-impl Mul for Samples {
+impl Mul<calc::Samples> for Samples {
     type Output = Self;
     #[inline]
-    fn mul(self, rhs: Self) -> Self {
-        Self(self.samples() * rhs.samples())
+    fn mul(self, rhs: calc::Samples) -> Self {
+        Self(self.samples() * rhs)
     }
 }
 
-impl Div for Samples {
+impl Mul<Samples> for calc::Samples {
+    type Output = Samples;
+    #[inline]
+    fn mul(self, rhs: Samples) -> Samples {
+        Samples(self * rhs.samples())
+    }
+}
+
+impl Div<calc::Samples> for Samples {
     type Output = Self;
     #[inline]
-    fn div(self, rhs: Self) -> Self {
-        Self(self.samples() / rhs.samples())
+    fn div(self, rhs: calc::Samples) -> Self {
+        Self(self.samples() / rhs)
+    }
+}
+
+impl Div for Samples {
+    type Output = f64;
+    #[inline]
+    fn div(self, rhs: Self) -> f64 {
+        self.samples() as f64 / rhs.samples() as f64
     }
 }
 
@@ -135,14 +288,14 @@ impl SubAssign for Samples {
     }
 }
 
-impl MulAssign for Samples {
-    fn mul_assign(&mut self, rhs: Self) {
+impl MulAssign<calc::Samples> for Samples {
+    fn mul_assign(&mut self, rhs: calc::Samples) {
         *self = *self * rhs;
     }
 }
 
-impl DivAssign for Samples {
-    fn div_assign(&mut self, rhs: Self) {
+impl DivAssign<calc::Samples> for Samples {
+    fn div_assign(&mut self, rhs: calc::Samples) {
         *self = *self / rhs;
     }
 }
@@ -153,6 +306,18 @@ impl RemAssign for Samples {
     }
 }
 
+impl Sum for Samples {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Samples(0), |total, x| total + x)
+    }
+}
+
+impl<'a> Sum<&'a Samples> for Samples {
+    fn sum<I: Iterator<Item = &'a Samples>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
 impl ToPrimitive for Samples {
     fn to_u64(&self) -> Option<u64> {
         self.samples().to_u64()
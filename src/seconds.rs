@@ -0,0 +1,245 @@
+//! Time representation in the form of Seconds.
+
+use num::{FromPrimitive, ToPrimitive};
+use std::iter::Sum;
+use std::ops::{Add, Sub, Mul, Div, Rem, Neg,
+               AddAssign, SubAssign, MulAssign, DivAssign, RemAssign};
+use std::time::Duration;
+use crate::calc;
+use crate::duration::{duration_from_secs, DurationError};
+use crate::{
+    Bpm,
+    Ms,
+    Ppqn,
+    SampleHz,
+    Samples,
+    Ticks,
+    TimeSig,
+    SECOND_IN_MS,
+};
+
+/// Time representation in the form of Seconds.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Seconds(pub calc::Seconds);
+
+impl Seconds {
+
+    /// Return the unit value of Seconds.
+    #[inline]
+    pub fn seconds(&self) -> calc::Seconds { let Seconds(secs) = *self; secs }
+
+    /// Convert to the equivalent duration in Bars.
+    #[inline]
+    pub fn bars(&self, bpm: impl Into<Bpm>, ts: TimeSig) -> f64 {
+        self.to_ms().bars(bpm, ts)
+    }
+
+    /// Convert to the equivalent duration in Beats.
+    #[inline]
+    pub fn beats(&self, bpm: impl Into<Bpm>) -> f64 {
+        self.to_ms().beats(bpm)
+    }
+
+    /// Convert to the unit value of `Ms`.
+    #[inline]
+    pub fn ms(&self) -> calc::Ms {
+        self.seconds() * SECOND_IN_MS
+    }
+    /// Convert to `Ms`.
+    #[inline]
+    pub fn to_ms(&self) -> Ms {
+        Ms(self.ms())
+    }
+
+    /// Convert to the unit value of `Samples`.
+    #[inline]
+    pub fn samples(&self, sample_hz: SampleHz) -> calc::Samples {
+        self.to_ms().samples(sample_hz)
+    }
+    /// Convert to `Samples`.
+    #[inline]
+    pub fn to_samples(&self, sample_hz: SampleHz) -> Samples {
+        Samples(self.samples(sample_hz))
+    }
+
+    /// Convert to the unit value of `Ticks`.
+    #[inline]
+    pub fn ticks(&self, bpm: impl Into<Bpm>, ppqn: Ppqn) -> calc::Ticks {
+        self.to_ms().ticks(bpm, ppqn)
+    }
+    /// Convert to `Ticks`.
+    #[inline]
+    pub fn to_ticks(&self, bpm: impl Into<Bpm>, ppqn: Ppqn) -> Ticks {
+        Ticks(self.ticks(bpm, ppqn))
+    }
+
+    /// Convert to a `Duration`.
+    ///
+    /// Fails if the value is negative, not finite or too large for a `Duration`.
+    #[inline]
+    pub fn to_duration(&self) -> Result<Duration, DurationError> {
+        duration_from_secs(self.seconds())
+    }
+
+}
+
+impl From<calc::Seconds> for Seconds {
+    #[inline]
+    fn from(secs: calc::Seconds) -> Self {
+        Seconds(secs)
+    }
+}
+
+impl From<Ms> for Seconds {
+    #[inline]
+    fn from(ms: Ms) -> Self {
+        Seconds(ms.ms() / SECOND_IN_MS)
+    }
+}
+
+impl From<Seconds> for Ms {
+    #[inline]
+    fn from(secs: Seconds) -> Self {
+        secs.to_ms()
+    }
+}
+
+impl From<Duration> for Seconds {
+    #[inline]
+    fn from(duration: Duration) -> Self {
+        Seconds(duration.as_secs_f64())
+    }
+}
+
+impl TryFrom<Seconds> for Duration {
+    type Error = DurationError;
+    #[inline]
+    fn try_from(secs: Seconds) -> Result<Self, Self::Error> {
+        secs.to_duration()
+    }
+}
+
+impl Add for Seconds {
+    type Output = Self;
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        Self(self.seconds() + rhs.seconds())
+    }
+}
+
+impl Sub for Seconds {
+    type Output = Self;
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.seconds() - rhs.seconds())
+    }
+}
+
+impl Mul<f64> for Seconds {
+    type Output = Self;
+    #[inline]
+    fn mul(self, rhs: f64) -> Self {
+        Self(self.seconds() * rhs)
+    }
+}
+
+impl Mul<Seconds> for f64 {
+    type Output = Seconds;
+    #[inline]
+    fn mul(self, rhs: Seconds) -> Seconds {
+        Seconds(self * rhs.seconds())
+    }
+}
+
+impl Div<f64> for Seconds {
+    type Output = Self;
+    #[inline]
+    fn div(self, rhs: f64) -> Self {
+        Self(self.seconds() / rhs)
+    }
+}
+
+impl Div for Seconds {
+    type Output = f64;
+    #[inline]
+    fn div(self, rhs: Self) -> f64 {
+        self.seconds() / rhs.seconds()
+    }
+}
+
+impl Rem for Seconds {
+    type Output = Self;
+    #[inline]
+    fn rem(self, rhs: Self) -> Self {
+        Self(self.seconds() % rhs.seconds())
+    }
+}
+
+impl Neg for Seconds {
+    type Output = Self;
+    #[inline]
+    fn neg(self) -> Self {
+        Self(-self.seconds())
+    }
+}
+
+impl AddAssign for Seconds {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Seconds {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign<f64> for Seconds {
+    fn mul_assign(&mut self, rhs: f64) {
+        *self = *self * rhs;
+    }
+}
+
+impl DivAssign<f64> for Seconds {
+    fn div_assign(&mut self, rhs: f64) {
+        *self = *self / rhs;
+    }
+}
+
+impl RemAssign for Seconds {
+    fn rem_assign(&mut self, rhs: Self) {
+        *self = *self % rhs;
+    }
+}
+
+impl Sum for Seconds {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Seconds(0.0), |total, x| total + x)
+    }
+}
+
+impl<'a> Sum<&'a Seconds> for Seconds {
+    fn sum<I: Iterator<Item = &'a Seconds>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+impl ToPrimitive for Seconds {
+    fn to_u64(&self) -> Option<u64> {
+        self.seconds().to_u64()
+    }
+    fn to_i64(&self) -> Option<i64> {
+        self.seconds().to_i64()
+    }
+}
+
+impl FromPrimitive for Seconds {
+    fn from_u64(n: u64) -> Option<Seconds> {
+        Some(Seconds(n as calc::Seconds))
+    }
+    fn from_i64(n: i64) -> Option<Seconds> {
+        Some(Seconds(n as calc::Seconds))
+    }
+}
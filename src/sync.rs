@@ -0,0 +1,133 @@
+//! Tempo-synced periods and rates for delays, LFOs and other modulators.
+
+use crate::{
+    Bpm,
+    Division,
+    DivType,
+    Measure,
+    Ms,
+    NumDiv,
+    SampleHz,
+    Samples,
+    TimeSig,
+    SECOND_IN_MS,
+};
+
+/// Every `DivType` in the order they are listed within a `sync_table`.
+const DIV_TYPES: [DivType; 3] = [DivType::Whole, DivType::Dotted, DivType::TwoThirds];
+
+/// A single row of a `sync_table`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SyncEntry {
+    /// The musical duration of the period.
+    pub measure: Measure,
+    /// The period in milliseconds.
+    pub ms: Ms,
+    /// The period in samples.
+    pub samples: Samples,
+    /// The rate at which the period repeats in hertz.
+    pub hz: f64,
+}
+
+/// The period of the given musical duration in milliseconds.
+#[inline]
+pub fn period_ms(measure: Measure, bpm: impl Into<Bpm>, ts: TimeSig) -> Ms {
+    measure.to_ms(bpm, ts)
+}
+
+/// The period of the given musical duration in samples.
+#[inline]
+pub fn period_samples(measure: Measure, bpm: impl Into<Bpm>, ts: TimeSig, sample_hz: SampleHz) -> Samples {
+    measure.to_samples(bpm, ts, sample_hz)
+}
+
+/// The rate in hertz at which the given musical duration repeats.
+#[inline]
+pub fn frequency_hz(measure: Measure, bpm: impl Into<Bpm>, ts: TimeSig) -> f64 {
+    SECOND_IN_MS / measure.ms(bpm, ts)
+}
+
+/// The musical duration nearest to the given period.
+///
+/// Candidates are a single straight, dotted or triplet of every `Division` along with any whole
+/// number of bars. Closeness is measured as a ratio, so that 10% too long and 10% too short are
+/// considered equally near.
+///
+/// Returns `None` if `ms` is not positive and finite, or if no candidate has a positive and finite
+/// duration at the given tempo and meter.
+pub fn nearest_measure_from_ms(ms: Ms, bpm: impl Into<Bpm>, ts: TimeSig) -> Option<Measure> {
+    let (target, bpm) = (ms.ms(), bpm.into());
+    if !(target > 0.0 && target.is_finite()) {
+        return None;
+    }
+    let distance = |m: &Measure| (m.ms(bpm, ts) / target).ln().abs();
+    let bars = (ms.bars(bpm, ts).round() as NumDiv).max(1);
+    single_divisions()
+        .chain(Some(Measure(bars, Division::Bar, DivType::Whole)))
+        .filter(|m| distance(m).is_finite())
+        .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+}
+
+/// The musical duration whose rate is nearest to the given frequency in hertz.
+///
+/// Returns `None` if `hz` is not positive and finite, or if no candidate has a positive and finite
+/// rate at the given tempo and meter.
+#[inline]
+pub fn nearest_measure_from_hz(hz: f64, bpm: impl Into<Bpm>, ts: TimeSig) -> Option<Measure> {
+    nearest_measure_from_ms(Ms(SECOND_IN_MS / hz), bpm, ts)
+}
+
+/// List the period and rate of every `Division` as a straight, dotted and triplet value at the
+/// given tempo.
+///
+/// Entries are ordered from the longest `Division` to the shortest.
+pub fn sync_table(bpm: impl Into<Bpm>, ts: TimeSig, sample_hz: SampleHz) -> Vec<SyncEntry> {
+    let bpm = bpm.into();
+    single_divisions()
+        .map(|measure| SyncEntry {
+            measure,
+            ms: period_ms(measure, bpm, ts),
+            samples: period_samples(measure, bpm, ts, sample_hz),
+            hz: frequency_hz(measure, bpm, ts),
+        })
+        .collect()
+}
+
+/// A single straight, dotted and triplet of every `Division`, longest first.
+fn single_divisions() -> impl Iterator<Item = Measure> {
+    let mut div = Some(Division::Bar);
+    std::iter::from_fn(move || {
+        let current = div?;
+        div = current.zoom_in(1);
+        Some(current)
+    })
+    .flat_map(|div| DIV_TYPES.iter().map(move |&div_type| Measure(1, div, div_type)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nearest_measure() {
+        let ts = TimeSig { top: 4, bottom: 4 };
+        let beat = Measure(1, Division::Beat, DivType::Whole);
+        assert_eq!(nearest_measure_from_ms(Ms(510.0), 120.0, ts), Some(beat));
+        assert_eq!(nearest_measure_from_hz(2.0, 120.0, ts), Some(beat));
+        let bars = Measure(3, Division::Bar, DivType::Whole);
+        assert_eq!(nearest_measure_from_ms(Ms(6_000.0), 120.0, ts), Some(bars));
+    }
+
+    #[test]
+    fn nearest_measure_rejects_invalid() {
+        let ts = TimeSig { top: 4, bottom: 4 };
+        for ms in [0.0, -500.0, f64::NAN, f64::INFINITY] {
+            assert_eq!(nearest_measure_from_ms(Ms(ms), 120.0, ts), None);
+        }
+        for hz in [0.0, -2.0, f64::NAN, f64::INFINITY] {
+            assert_eq!(nearest_measure_from_hz(hz, 120.0, ts), None);
+        }
+        assert_eq!(nearest_measure_from_ms(Ms(500.0), f64::NAN, ts), None);
+        assert_eq!(nearest_measure_from_ms(Ms(500.0), 0.0, ts), None);
+    }
+}
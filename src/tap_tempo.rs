@@ -0,0 +1,208 @@
+//! Estimating tempo from taps.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+use crate::calc;
+use crate::phase::wrap_phase;
+use crate::{
+    Bpm,
+    Ms,
+    SampleHz,
+    Samples,
+    beat_in_ms,
+    ms_from_samples,
+    MINUTE_IN_MS,
+};
+
+/// The number of consecutive outliers after which the tapper is assumed to have changed tempo.
+const MAX_CONSECUTIVE_OUTLIERS: usize = 2;
+
+/// The resolution to which a `TapTempo` estimate is snapped.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TempoSnap {
+    /// Don't snap the estimate.
+    None,
+    /// Snap to the nearest whole BPM.
+    Whole,
+    /// Snap to the nearest half BPM.
+    Half,
+}
+
+/// A tempo estimate produced by `TapTempo`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TapEstimate {
+    /// The estimated tempo.
+    pub bpm: Bpm,
+    /// How confident the estimate is in the range `[0, 1]`.
+    ///
+    /// This grows as the averaging window fills and shrinks as the intervals between taps vary.
+    pub confidence: f64,
+    /// The time of the most recent tap that was not rejected as an outlier, which is assumed to
+    /// land on a beat.
+    pub last_tap: Ms,
+}
+
+impl TapEstimate {
+
+    /// The normalized phase in `[0, 1)` of the beat at the given time.
+    ///
+    /// The transport can be re-aligned to the taps by seeking to the beat with this phase.
+    #[inline]
+    pub fn beat_phase(&self, now: Ms) -> f64 {
+        wrap_phase((now - self.last_tap).ms() / beat_in_ms(self.bpm))
+    }
+
+}
+
+/// Estimates a tempo from a sequence of tap timestamps.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TapTempo {
+    /// The maximum number of intervals between taps that are averaged.
+    pub window: usize,
+    /// The estimate is reset if no tap occurs for this long.
+    pub timeout: Ms,
+    /// The fraction by which an interval may differ from the median interval before it is
+    /// rejected as an outlier.
+    pub tolerance: f64,
+    /// The resolution to which the estimate is snapped.
+    pub snap: TempoSnap,
+    intervals: VecDeque<calc::Ms>,
+    /// The most recent tap, including outliers.
+    last_tap: Option<Ms>,
+    /// The most recent tap that was not rejected as an outlier.
+    anchor: Option<Ms>,
+    consecutive_outliers: usize,
+}
+
+impl TapTempo {
+
+    /// Construct a `TapTempo` that averages up to `window` intervals and resets after `timeout`.
+    pub fn new(window: usize, timeout: Ms) -> TapTempo {
+        TapTempo {
+            window: window.max(1),
+            timeout,
+            tolerance: 0.25,
+            snap: TempoSnap::None,
+            intervals: VecDeque::with_capacity(window),
+            last_tap: None,
+            anchor: None,
+            consecutive_outliers: 0,
+        }
+    }
+
+    /// Forget all previous taps.
+    pub fn reset(&mut self) {
+        self.intervals.clear();
+        self.last_tap = None;
+        self.anchor = None;
+        self.consecutive_outliers = 0;
+    }
+
+    /// Register a tap at the given time, returning the updated estimate.
+    ///
+    /// Taps that do not come after the previous tap are ignored.
+    pub fn tap(&mut self, time: Ms) -> Option<TapEstimate> {
+        let (previous, anchor) = match (self.last_tap, self.anchor) {
+            (Some(last), _) if time <= last => return self.estimate(),
+            (Some(last), Some(anchor)) if time - last <= self.timeout => (last, anchor),
+            _ => {
+                self.reset();
+                self.last_tap = Some(time);
+                self.anchor = Some(time);
+                return None;
+            },
+        };
+        self.last_tap = Some(time);
+
+        let mut interval = (time - anchor).ms();
+        if let Some(median) = self.median_interval() {
+            if ((interval - median) / median).abs() > self.tolerance {
+                self.consecutive_outliers += 1;
+                if self.consecutive_outliers < MAX_CONSECUTIVE_OUTLIERS {
+                    return self.estimate();
+                }
+                // The outliers agree with one another, so the tempo has likely changed.
+                self.intervals.clear();
+                interval = (time - previous).ms();
+            }
+        }
+
+        self.consecutive_outliers = 0;
+        self.anchor = Some(time);
+        self.intervals.push_back(interval);
+        while self.intervals.len() > self.window {
+            self.intervals.pop_front();
+        }
+        self.estimate()
+    }
+
+    /// Register a tap at the given time in `Samples`.
+    #[inline]
+    pub fn tap_samples(&mut self, time: Samples, sample_hz: SampleHz) -> Option<TapEstimate> {
+        self.tap(Ms(ms_from_samples(time.samples(), sample_hz)))
+    }
+
+    /// Register a tap at the given time since some fixed epoch (i.e. `Instant::elapsed`).
+    #[inline]
+    pub fn tap_duration(&mut self, time: Duration) -> Option<TapEstimate> {
+        self.tap(Ms::from(time))
+    }
+
+    /// The current estimate, or `None` if fewer than two taps have been registered.
+    pub fn estimate(&self) -> Option<TapEstimate> {
+        let last_tap = self.anchor?;
+        let n = self.intervals.len();
+        if n == 0 {
+            return None;
+        }
+        let mean = self.intervals.iter().sum::<calc::Ms>() / n as f64;
+        let variance = self.intervals.iter().map(|i| (i - mean).powi(2)).sum::<f64>() / n as f64;
+        let variation = variance.sqrt() / mean;
+        let consistency = (1.0 - variation / self.tolerance).clamp(0.0, 1.0);
+        let fill = n as f64 / self.window as f64;
+        let bpm = MINUTE_IN_MS / mean;
+        let bpm = match self.snap {
+            TempoSnap::None => bpm,
+            TempoSnap::Whole => bpm.round(),
+            TempoSnap::Half => (bpm * 2.0).round() / 2.0,
+        };
+        Some(TapEstimate { bpm, confidence: consistency * fill, last_tap })
+    }
+
+    /// The median of the intervals within the window.
+    fn median_interval(&self) -> Option<calc::Ms> {
+        if self.intervals.is_empty() {
+            return None;
+        }
+        let mut sorted: Vec<calc::Ms> = self.intervals.iter().copied().collect();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let mid = sorted.len() / 2;
+        if sorted.len().is_multiple_of(2) {
+            Some((sorted[mid - 1] + sorted[mid]) / 2.0)
+        } else {
+            Some(sorted[mid])
+        }
+    }
+
+}
+
+impl Default for TapTempo {
+    /// Averages up to 8 intervals and resets after 2 seconds without a tap.
+    fn default() -> Self {
+        TapTempo::new(8, Ms(2_000.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn beat_phase_stays_below_one() {
+        let estimate = TapEstimate { bpm: 120.0, confidence: 1.0, last_tap: Ms(0.0) };
+        assert_eq!(estimate.beat_phase(Ms(-1e-15)), 0.0);
+        assert_eq!(estimate.beat_phase(Ms(-125.0)), 0.75);
+        assert_eq!(estimate.beat_phase(Ms(1_250.0)), 0.5);
+    }
+}
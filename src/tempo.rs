@@ -0,0 +1,114 @@
+//! Tempo referenced to a beat unit other than the quarter note.
+
+use std::fmt;
+use crate::calc;
+use crate::{
+    Bpm,
+    Division,
+    Measure,
+    TimeSig,
+    MINUTE_IN_MS,
+};
+
+/// A tempo as written in a score, i.e. "dotted quarter = 60" or "half = 80".
+///
+/// `Bpm` elsewhere within the crate counts quarter notes per minute. A `Tempo` may be passed
+/// anywhere a `Bpm` is accepted, in which case it is converted with `quarter_bpm`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Tempo {
+    /// The number of beat units per minute.
+    pub bpm: Bpm,
+    /// The note value of the beat unit. `Division::Bar` is treated as a whole note.
+    pub div: Division,
+    /// The number of dots extending the beat unit.
+    pub dots: u8,
+}
+
+impl Tempo {
+
+    /// Construct a tempo of `bpm` beat units per minute.
+    #[inline]
+    pub fn new(bpm: Bpm, div: Division, dots: u8) -> Tempo {
+        Tempo { bpm, div, dots }
+    }
+
+    /// Construct a tempo counting quarter notes.
+    #[inline]
+    pub fn quarter(bpm: Bpm) -> Tempo {
+        Tempo::new(bpm, Division::Beat, 0)
+    }
+
+    /// Construct the tempo with the given beat unit equivalent to `quarter_bpm` quarter notes per
+    /// minute.
+    #[inline]
+    pub fn from_quarter_bpm(quarter_bpm: Bpm, div: Division, dots: u8) -> Tempo {
+        let unit = Tempo::new(1.0, div, dots).beat_in_quarters();
+        Tempo::new(quarter_bpm / unit, div, dots)
+    }
+
+    /// The duration of the beat unit in quarter notes.
+    pub fn beat_in_quarters(&self) -> f64 {
+        let undotted = match self.div {
+            Division::Bar => 4.0,
+            div => 2.0f64.powi(Division::Beat as i32 - div as i32),
+        };
+        // Each dot adds half of the previous value.
+        undotted * (2.0 - 0.5f64.powi(self.dots as i32))
+    }
+
+    /// The equivalent number of quarter notes per minute.
+    #[inline]
+    pub fn quarter_bpm(&self) -> Bpm {
+        self.bpm * self.beat_in_quarters()
+    }
+
+    /// The duration of the beat unit in milliseconds.
+    #[inline]
+    pub fn beat_unit_in_ms(&self) -> calc::Ms {
+        MINUTE_IN_MS / self.bpm
+    }
+
+}
+
+impl From<Tempo> for Bpm {
+    fn from(tempo: Tempo) -> Self {
+        tempo.quarter_bpm()
+    }
+}
+
+impl fmt::Display for Tempo {
+    /// Displays as i.e. `dotted quarter = 60`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let dots = match self.dots {
+            0 => "",
+            1 => "dotted ",
+            2 => "double dotted ",
+            _ => "multiply dotted ",
+        };
+        let note = match self.div {
+            Division::Bar => "whole",
+            Division::Minim => "half",
+            Division::Beat => "quarter",
+            Division::Quaver => "eighth",
+            Division::SemiQuaver => "sixteenth",
+            Division::ThirtySecond => "thirty-second",
+            Division::SixtyFourth => "sixty-fourth",
+            Division::OneHundredTwentyEighth => "128th",
+            Division::TwoHundredFiftySixth => "256th",
+            Division::FiveHundredTwelfth => "512th",
+            Division::OneThousandTwentyFourth => "1024th",
+        };
+        write!(f, "{}{} = {}", dots, note, self.bpm)
+    }
+}
+
+/// The tempo resulting from a metric modulation in which the `new` note value at the new tempo
+/// lasts as long as the `old` note value at `old_bpm`.
+///
+/// For example, "new quarter = old dotted eighth" is `metric_modulation(bpm, dotted_eighth,
+/// quarter, ts)`.
+#[inline]
+pub fn metric_modulation(old_bpm: impl Into<Bpm>, old: Measure, new: Measure, ts: TimeSig) -> Bpm {
+    old_bpm.into() * new.beats(ts) / old.beats(ts)
+}
@@ -0,0 +1,192 @@
+//! Offline estimation of tempo and downbeat from detected onsets.
+//!
+//! Tempo candidates are found using a histogram of the intervals between onsets, each folded by
+//! octaves into the preferred tempo range. The beat phase of each candidate is the circular mean
+//! of the onset phases, and the downbeat is the beat of the bar on which the most onsets land.
+
+use std::f64::consts::PI;
+use crate::calc;
+use crate::{
+    Bpm,
+    Ppqn,
+    SampleHz,
+    Samples,
+    Ticks,
+    TimeSig,
+    MINUTE_IN_MS,
+    SECOND_IN_MS,
+    samples_from_ticks,
+    ticks_from_samples,
+};
+
+/// The width of each bin within the interval histogram.
+const BIN_BPM: Bpm = 0.5;
+/// The maximum number of bins within the interval histogram.
+const MAX_BINS: f64 = 65_536.0;
+/// The maximum number of candidates returned.
+const MAX_CANDIDATES: usize = 5;
+/// Intervals spanning more than this many beats at the lowest tempo are ignored.
+const MAX_INTERVAL_BEATS: f64 = 4.0;
+/// An onset within this fraction of a beat of the downbeat counts towards it.
+const DOWNBEAT_TOLERANCE: f64 = 0.1;
+
+/// A candidate tempo and beat grid proposed by `estimate_tempo`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TempoCandidate {
+    /// The estimated tempo.
+    pub bpm: Bpm,
+    /// The strength of the candidate relative to the strongest, in the range `(0, 1]`.
+    pub score: f64,
+    /// The position of the first downbeat at or after `Samples(0)`.
+    pub downbeat: Samples,
+    /// The sample rate of the onsets.
+    pub sample_hz: SampleHz,
+}
+
+impl TempoCandidate {
+
+    /// The position in `Samples` of the given musical position within the beat grid, where
+    /// `Ticks(0)` is the first downbeat.
+    #[inline]
+    pub fn samples_at(&self, ticks: Ticks, ppqn: Ppqn) -> Samples {
+        let offset = samples_from_ticks(ticks.ticks(), self.bpm, ppqn, self.sample_hz);
+        self.downbeat + Samples(offset)
+    }
+
+    /// The musical position within the beat grid of the given position in `Samples`, where
+    /// `Ticks(0)` is the first downbeat.
+    #[inline]
+    pub fn ticks_at(&self, samples: Samples, ppqn: Ppqn) -> Ticks {
+        let offset = samples - self.downbeat;
+        Ticks(ticks_from_samples(offset.samples(), self.bpm, ppqn, self.sample_hz))
+    }
+
+}
+
+/// Propose the most likely tempos within `min_bpm..=max_bpm` for the given onsets, strongest
+/// first.
+///
+/// Returns an empty `Vec` if there are fewer than two onsets, or the range is empty, not finite
+/// or wider than 32768 BPM.
+pub fn estimate_tempo(onsets: &[Samples], sample_hz: SampleHz, ts: TimeSig,
+                      min_bpm: Bpm, max_bpm: Bpm) -> Vec<TempoCandidate> {
+    if onsets.len() < 2 || !(min_bpm > 0.0 && min_bpm < max_bpm && max_bpm.is_finite()) {
+        return Vec::new();
+    }
+    if (max_bpm - min_bpm) / BIN_BPM > MAX_BINS {
+        return Vec::new();
+    }
+    let mut onsets = onsets.to_vec();
+    onsets.sort();
+
+    let histogram = interval_histogram(&onsets, sample_hz, min_bpm, max_bpm);
+    let mut peaks: Vec<(Bpm, f64)> = (0..histogram.len())
+        .filter(|&i| {
+            let h = histogram[i];
+            h > 0.0
+                && (i == 0 || histogram[i - 1] < h)
+                && (i + 1 == histogram.len() || histogram[i + 1] <= h)
+        })
+        .map(|i| (refine_peak(&histogram, i, min_bpm), histogram[i]))
+        .collect();
+    peaks.sort_by(|a, b| b.1.total_cmp(&a.1));
+    peaks.truncate(MAX_CANDIDATES);
+
+    let strongest = match peaks.first() {
+        Some(&(_, weight)) => weight,
+        None => return Vec::new(),
+    };
+    peaks.into_iter()
+        .map(|(bpm, weight)| TempoCandidate {
+            bpm,
+            score: weight / strongest,
+            downbeat: downbeat(&onsets, sample_hz, ts, bpm),
+            sample_hz,
+        })
+        .collect()
+}
+
+/// A smoothed histogram of the tempos implied by the intervals between each pair of onsets.
+fn interval_histogram(onsets: &[Samples], sample_hz: SampleHz,
+                      min_bpm: Bpm, max_bpm: Bpm) -> Vec<f64> {
+    let num_bins = ((max_bpm - min_bpm) / BIN_BPM).ceil() as usize + 1;
+    let mut bins = vec![0.0; num_bins];
+    let max_interval_ms = MAX_INTERVAL_BEATS * MINUTE_IN_MS / min_bpm;
+    for (i, a) in onsets.iter().enumerate() {
+        for (n, b) in onsets[i + 1..].iter().enumerate() {
+            let interval_ms = (*b - *a).samples() as calc::Ms * SECOND_IN_MS / sample_hz;
+            if interval_ms > max_interval_ms {
+                break;
+            }
+            if interval_ms <= 0.0 {
+                continue;
+            }
+            let mut bpm = MINUTE_IN_MS / interval_ms;
+            while bpm > max_bpm {
+                bpm /= 2.0;
+            }
+            while bpm < min_bpm {
+                bpm *= 2.0;
+            }
+            if bpm > max_bpm {
+                continue;
+            }
+            // Intervals between neighbouring onsets are the most reliable.
+            bins[((bpm - min_bpm) / BIN_BPM).round() as usize] += 1.0 / (n + 1) as f64;
+        }
+    }
+    (0..num_bins)
+        .map(|i| {
+            let prev = if i > 0 { bins[i - 1] } else { 0.0 };
+            let next = if i + 1 < num_bins { bins[i + 1] } else { 0.0 };
+            0.25 * prev + 0.5 * bins[i] + 0.25 * next
+        })
+        .collect()
+}
+
+/// The tempo at the given peak, refined between bins by parabolic interpolation.
+fn refine_peak(histogram: &[f64], i: usize, min_bpm: Bpm) -> Bpm {
+    let centre = min_bpm + i as f64 * BIN_BPM;
+    if i == 0 || i + 1 == histogram.len() {
+        return centre;
+    }
+    let (a, b, c) = (histogram[i - 1], histogram[i], histogram[i + 1]);
+    let denom = a - 2.0 * b + c;
+    if denom == 0.0 {
+        return centre;
+    }
+    centre + 0.5 * (a - c) / denom * BIN_BPM
+}
+
+/// The position of the first downbeat at or after `Samples(0)` for the given tempo.
+fn downbeat(onsets: &[Samples], sample_hz: SampleHz, ts: TimeSig, bpm: Bpm) -> Samples {
+    let beat = MINUTE_IN_MS / bpm * sample_hz / SECOND_IN_MS;
+
+    // The circular mean of each onset's phase within the beat.
+    let (sin, cos) = onsets.iter().fold((0.0, 0.0), |(sin, cos), onset| {
+        let angle = 2.0 * PI * onset.samples() as f64 / beat;
+        (sin + angle.sin(), cos + angle.cos())
+    });
+    let phase = sin.atan2(cos).rem_euclid(2.0 * PI) / (2.0 * PI);
+    let beat_offset = phase * beat;
+
+    // The beat of the bar on which the most onsets land.
+    let beats_per_bar = (ts.beats_per_bar().round() as usize).max(1);
+    let bar = beat * beats_per_bar as f64;
+    let tolerance = beat * DOWNBEAT_TOLERANCE;
+    let hits = |candidate: f64| {
+        onsets.iter()
+            .filter(|onset| {
+                let d = (onset.samples() as f64 - candidate).rem_euclid(bar);
+                d < tolerance || bar - d < tolerance
+            })
+            .count()
+    };
+    // `max_by_key` keeps the last of equal maxima, so iterate in reverse to break ties toward
+    // the earliest beat of the bar.
+    let best = (0..beats_per_bar).rev()
+        .map(|k| beat_offset + k as f64 * beat)
+        .max_by_key(|&candidate| hits(candidate))
+        .unwrap_or(beat_offset);
+    Samples(best.rem_euclid(bar).round() as calc::Samples)
+}
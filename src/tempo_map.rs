@@ -0,0 +1,362 @@
+//! A map of tempo changes over musical time.
+
+use std::error::Error;
+use std::fmt;
+use crate::calc;
+use crate::{
+    Bpm,
+    Measure,
+    Ms,
+    Ppqn,
+    SampleHz,
+    Samples,
+    TempoRemap,
+    Ticks,
+    TimeSig,
+    beat_in_ms,
+    metric_modulation,
+    ms_from_samples,
+    samples_from_ms,
+};
+
+/// A change to the given tempo at a musical position.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TempoChange {
+    pub ticks: Ticks,
+    pub bpm: Bpm,
+}
+
+/// Maps musical positions in `Ticks` to absolute time, following a sequence of tempo changes.
+///
+/// The tempo is constant between changes. There is always a change at `Ticks(0)` which is also
+/// the tempo used for negative positions. Absolute time is measured from `Ticks(0)`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "TempoMapChanges"))]
+pub struct TempoMap {
+    ppqn: Ppqn,
+    changes: Vec<TempoChange>,
+    /// The time in milliseconds at which each change begins.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    starts_ms: Vec<calc::Ms>,
+}
+
+/// The reasons a `TempoMap` may not be constructed or edited.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum TempoMapError {
+    /// A tempo was not positive and finite.
+    InvalidBpm,
+    /// The resolution was `0`.
+    InvalidPpqn,
+    /// The first change was not at `Ticks(0)`.
+    NoChangeAtZero,
+    /// The changes were not strictly ordered by position.
+    NotOrdered,
+}
+
+impl fmt::Display for TempoMapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match *self {
+            TempoMapError::InvalidBpm => "tempo is not positive and finite",
+            TempoMapError::InvalidPpqn => "ppqn must be greater than 0",
+            TempoMapError::NoChangeAtZero => "a tempo map must begin with a change at tick 0",
+            TempoMapError::NotOrdered => "tempo changes must be strictly ordered by position",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl Error for TempoMapError {}
+
+/// The serialized form of a `TempoMap`, from which the start times are rebuilt.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct TempoMapChanges {
+    ppqn: Ppqn,
+    changes: Vec<TempoChange>,
+}
+
+impl TempoMap {
+
+    /// Construct a map with a constant tempo.
+    ///
+    /// Returns an error if `bpm` is not positive and finite or if `ppqn` is `0`.
+    pub fn new(bpm: impl Into<Bpm>, ppqn: Ppqn) -> Result<TempoMap, TempoMapError> {
+        let bpm = check_bpm(bpm.into())?;
+        if ppqn == 0 {
+            return Err(TempoMapError::InvalidPpqn);
+        }
+        Ok(TempoMap {
+            ppqn,
+            changes: vec![TempoChange { ticks: Ticks(0), bpm }],
+            starts_ms: vec![0.0],
+        })
+    }
+
+    /// The resolution of the `Ticks` within the map.
+    #[inline]
+    pub fn ppqn(&self) -> Ppqn { self.ppqn }
+
+    /// The tempo changes ordered by position.
+    #[inline]
+    pub fn changes(&self) -> &[TempoChange] { &self.changes }
+
+    /// Insert a tempo change, replacing any existing change at the same position.
+    ///
+    /// A change at a negative position replaces the tempo at `Ticks(0)`. Returns an error, leaving
+    /// the map unchanged, if `bpm` is not positive and finite.
+    pub fn insert(&mut self, ticks: Ticks, bpm: impl Into<Bpm>) -> Result<(), TempoMapError> {
+        let bpm = check_bpm(bpm.into())?;
+        self.insert_change(ticks, bpm);
+        Ok(())
+    }
+
+    /// Remove the tempo change at exactly the given position.
+    ///
+    /// The change at `Ticks(0)` cannot be removed, so `None` is returned for it.
+    pub fn remove(&mut self, ticks: Ticks) -> Option<TempoChange> {
+        match self.changes.binary_search_by_key(&ticks, |c| c.ticks) {
+            Ok(i) if i > 0 => {
+                let change = self.changes.remove(i);
+                self.update_starts();
+                Some(change)
+            },
+            _ => None,
+        }
+    }
+
+    /// Insert a tempo change, returning the map before and after the edit so that existing
+    /// positions may be remapped.
+    ///
+    /// Returns an error, leaving the map unchanged, if `bpm` is not positive and finite.
+    pub fn edit_insert(&mut self, ticks: Ticks,
+                       bpm: impl Into<Bpm>) -> Result<TempoRemap, TempoMapError> {
+        let old = self.clone();
+        self.insert(ticks, bpm)?;
+        Ok(TempoRemap { old, new: self.clone() })
+    }
+
+    /// Remove the tempo change at exactly the given position, returning the map before and after
+    /// the edit.
+    ///
+    /// Returns `None` if there is no change at the position or if it is the change at `Ticks(0)`.
+    pub fn edit_remove(&mut self, ticks: Ticks) -> Option<TempoRemap> {
+        let old = self.clone();
+        self.remove(ticks)?;
+        Some(TempoRemap { old, new: self.clone() })
+    }
+
+    /// Move the tempo change at exactly `from` to `to`, replacing any change already there,
+    /// returning the map before and after the edit.
+    ///
+    /// Returns `None` if there is no change at `from` or if it is the change at `Ticks(0)`.
+    pub fn edit_move(&mut self, from: Ticks, to: Ticks) -> Option<TempoRemap> {
+        let old = self.clone();
+        let change = self.remove(from)?;
+        self.insert_change(to, change.bpm);
+        Some(TempoRemap { old, new: self.clone() })
+    }
+
+    /// Multiply the tempo between `start` and `end` by `factor`, leaving the tempo elsewhere
+    /// unchanged, returning the map before and after the edit.
+    ///
+    /// Returns `None`, leaving the map unchanged, if `factor` is not positive and finite or if a
+    /// scaled tempo would not be.
+    pub fn edit_scale(&mut self, start: Ticks, end: Ticks, factor: f64) -> Option<TempoRemap> {
+        if !(factor > 0.0 && factor.is_finite()) {
+            return None;
+        }
+        let old = self.clone();
+        let start = Ticks(start.ticks().max(0));
+        if start < end {
+            // Pin the tempo at either end of the range so that it only changes within.
+            self.insert_change(end, self.bpm_at(end));
+            self.insert_change(start, self.bpm_at(start));
+            for change in &mut self.changes {
+                if start <= change.ticks && change.ticks < end {
+                    change.bpm *= factor;
+                }
+            }
+            if self.changes.iter().any(|change| !(change.bpm > 0.0 && change.bpm.is_finite())) {
+                *self = old;
+                return None;
+            }
+            self.update_starts();
+        }
+        Some(TempoRemap { old, new: self.clone() })
+    }
+
+    /// Insert a metric modulation at the given position, in which the `new` note value lasts as
+    /// long as the `old` note value did at the tempo preceding the position. Returns the new tempo.
+    ///
+    /// Returns an error, leaving the map unchanged, if the new tempo is not positive and finite.
+    pub fn insert_modulation(&mut self, ticks: Ticks, old: Measure, new: Measure,
+                             ts: TimeSig) -> Result<Bpm, TempoMapError> {
+        let before = Ticks(ticks.ticks().max(1) - 1);
+        let bpm = metric_modulation(self.bpm_at(before), old, new, ts);
+        self.insert(ticks, bpm)?;
+        Ok(bpm)
+    }
+
+    /// The tempo at the given position.
+    #[inline]
+    pub fn bpm_at(&self, ticks: Ticks) -> Bpm {
+        self.changes[self.index_at_ticks(ticks.ticks() as f64)].bpm
+    }
+
+    /// The time in milliseconds at the given fractional position in beats.
+    pub fn ms_at_beats(&self, beats: f64) -> calc::Ms {
+        let ticks = beats * self.ppqn as f64;
+        let i = self.index_at_ticks(ticks);
+        let change = &self.changes[i];
+        let beats_into = (ticks - change.ticks.ticks() as f64) / self.ppqn as f64;
+        self.starts_ms[i] + beats_into * beat_in_ms(change.bpm)
+    }
+
+    /// The fractional position in beats at the given time in milliseconds.
+    pub fn beats_at_ms(&self, ms: calc::Ms) -> f64 {
+        let i = self.index_at_ms(ms);
+        self.beats_in_segment(i, ms)
+    }
+
+    /// Convert a position to `Ms`.
+    #[inline]
+    pub fn to_ms(&self, ticks: Ticks) -> Ms {
+        Ms(self.ms_at_beats(ticks.beats(self.ppqn)))
+    }
+
+    /// Convert a time to the nearest position in `Ticks`.
+    #[inline]
+    pub fn to_ticks(&self, ms: Ms) -> Ticks {
+        Ticks((self.beats_at_ms(ms.ms()) * self.ppqn as f64).round() as calc::Ticks)
+    }
+
+    /// Convert a position to `Samples`.
+    #[inline]
+    pub fn to_samples(&self, ticks: Ticks, sample_hz: SampleHz) -> Samples {
+        Samples(samples_from_ms(self.to_ms(ticks).ms(), sample_hz))
+    }
+
+    /// Convert a time in `Samples` to the nearest position in `Ticks`.
+    #[inline]
+    pub fn samples_to_ticks(&self, samples: Samples, sample_hz: SampleHz) -> Ticks {
+        self.to_ticks(Ms(ms_from_samples(samples.samples(), sample_hz)))
+    }
+
+    /// The index of the change in effect at the given fractional position in ticks.
+    pub(crate) fn index_at_ticks(&self, ticks: f64) -> usize {
+        self.changes.partition_point(|c| c.ticks.ticks() as f64 <= ticks).max(1) - 1
+    }
+
+    /// The index of the change in effect at the given time in milliseconds.
+    pub(crate) fn index_at_ms(&self, ms: calc::Ms) -> usize {
+        self.starts_ms.partition_point(|&start| start <= ms).max(1) - 1
+    }
+
+    /// The time in milliseconds at which the change at the given index begins.
+    #[inline]
+    pub(crate) fn start_ms(&self, index: usize) -> calc::Ms {
+        self.starts_ms[index]
+    }
+
+    /// The fractional position in beats at the given time, assuming that it falls within the
+    /// segment beginning at the change at the given index.
+    #[inline]
+    pub(crate) fn beats_in_segment(&self, index: usize, ms: calc::Ms) -> f64 {
+        let change = &self.changes[index];
+        let start_beats = change.ticks.beats(self.ppqn);
+        start_beats + (ms - self.starts_ms[index]) / beat_in_ms(change.bpm)
+    }
+
+    /// Insert a tempo change whose tempo is known to be positive and finite.
+    fn insert_change(&mut self, ticks: Ticks, bpm: Bpm) {
+        let ticks = Ticks(ticks.ticks().max(0));
+        match self.changes.binary_search_by_key(&ticks, |c| c.ticks) {
+            Ok(i) => self.changes[i].bpm = bpm,
+            Err(i) => self.changes.insert(i, TempoChange { ticks, bpm }),
+        }
+        self.update_starts();
+    }
+
+    /// Recalculate the time at which each change begins.
+    fn update_starts(&mut self) {
+        self.starts_ms.clear();
+        let mut ms = 0.0;
+        let mut prev: Option<&TempoChange> = None;
+        for change in &self.changes {
+            if let Some(prev) = prev {
+                let beats = (change.ticks - prev.ticks).beats(self.ppqn);
+                ms += beats * beat_in_ms(prev.bpm);
+            }
+            self.starts_ms.push(ms);
+            prev = Some(change);
+        }
+    }
+
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<TempoMapChanges> for TempoMap {
+    type Error = TempoMapError;
+    fn try_from(map: TempoMapChanges) -> Result<Self, Self::Error> {
+        if map.ppqn == 0 {
+            return Err(TempoMapError::InvalidPpqn);
+        }
+        if map.changes.first().map(|c| c.ticks) != Some(Ticks(0)) {
+            return Err(TempoMapError::NoChangeAtZero);
+        }
+        if map.changes.windows(2).any(|pair| pair[0].ticks >= pair[1].ticks) {
+            return Err(TempoMapError::NotOrdered);
+        }
+        for change in &map.changes {
+            check_bpm(change.bpm)?;
+        }
+        let mut tempo_map = TempoMap { ppqn: map.ppqn, changes: map.changes, starts_ms: Vec::new() };
+        tempo_map.update_starts();
+        Ok(tempo_map)
+    }
+}
+
+/// Returns `bpm` if it is positive and finite.
+#[inline]
+fn check_bpm(bpm: Bpm) -> Result<Bpm, TempoMapError> {
+    if bpm > 0.0 && bpm.is_finite() {
+        Ok(bpm)
+    } else {
+        Err(TempoMapError::InvalidBpm)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Division, DivType};
+
+    #[test]
+    fn rejects_invalid_tempo_and_resolution() {
+        for bpm in [-120.0, 0.0, f64::NAN, f64::INFINITY] {
+            assert_eq!(TempoMap::new(bpm, 960), Err(TempoMapError::InvalidBpm));
+        }
+        assert_eq!(TempoMap::new(120.0, 0), Err(TempoMapError::InvalidPpqn));
+
+        let mut map = TempoMap::new(120.0, 960).unwrap();
+        let before = map.clone();
+        assert_eq!(map.insert(Ticks(960), -60.0), Err(TempoMapError::InvalidBpm));
+        assert_eq!(map.edit_insert(Ticks(960), f64::NAN), Err(TempoMapError::InvalidBpm));
+        let ts = TimeSig { top: 4, bottom: 4 };
+        let zero = Measure(0, Division::Beat, DivType::Whole);
+        let beat = Measure(1, Division::Beat, DivType::Whole);
+        assert_eq!(map.insert_modulation(Ticks(960), zero, beat, ts), Err(TempoMapError::InvalidBpm));
+        assert_eq!(map, before);
+    }
+
+    #[test]
+    fn times_ascend() {
+        let mut map = TempoMap::new(120.0, 960).unwrap();
+        map.insert(Ticks(960), 60.0).unwrap();
+        assert_eq!(map.to_ms(Ticks(960)), Ms(500.0));
+        assert_eq!(map.to_ms(Ticks(1_920)), Ms(1_500.0));
+        assert_eq!(map.to_ticks(Ms(1_000.0)), Ticks(1_440));
+    }
+}
@@ -6,6 +6,10 @@
 //!
 
 use num::{FromPrimitive, ToPrimitive};
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use std::iter::Sum;
+use std::num::NonZeroU32;
 use std::ops::{Add, Sub, Mul, Div, Rem, Neg,
                AddAssign, SubAssign, MulAssign, DivAssign, RemAssign};
 use super::calc;
@@ -15,10 +19,13 @@ use super::{
     Ms,
     ms_from_ticks,
     Ppqn,
+    Rounding,
     SampleHz,
     Samples,
     samples_from_ticks,
     TimeSig,
+    common_ppqn,
+    ticks_from_ppqn,
 };
 
 /// Time representation in the form of Ticks.
@@ -45,27 +52,100 @@ impl Ticks {
 
     /// Convert to the unit value of `Ms`.
     #[inline]
-    pub fn ms(&self, bpm: Bpm, ppqn: Ppqn) -> calc::Ms {
+    pub fn ms(&self, bpm: impl Into<Bpm>, ppqn: Ppqn) -> calc::Ms {
         ms_from_ticks(self.ticks(), bpm, ppqn)
     }
     /// Convert to `Ms`.
     #[inline]
-    pub fn to_ms(&self, bpm: Bpm, ppqn: Ppqn) -> Ms {
+    pub fn to_ms(&self, bpm: impl Into<Bpm>, ppqn: Ppqn) -> Ms {
         Ms(self.ms(bpm, ppqn))
     }
 
     /// Convert to the unit value of `Samples`.
     #[inline]
-    pub fn samples(&self, bpm: Bpm, ppqn: Ppqn, sample_hz: SampleHz) -> calc::Samples {
+    pub fn samples(&self, bpm: impl Into<Bpm>, ppqn: Ppqn, sample_hz: SampleHz) -> calc::Samples {
         samples_from_ticks(self.ticks(), bpm, ppqn, sample_hz)
     }
 
     /// Convert to `Samples`.
     #[inline]
-    pub fn to_samples(&self, bpm: Bpm, ppqn: Ppqn, sample_hz: SampleHz) -> Samples {
+    pub fn to_samples(&self, bpm: impl Into<Bpm>, ppqn: Ppqn, sample_hz: SampleHz) -> Samples {
         Samples(self.samples(bpm, ppqn, sample_hz))
     }
 
+    /// Rescale from the `from` resolution to the `to` resolution, reporting any loss.
+    ///
+    /// Returns `None` if `from` is `0` or if the result overflows.
+    #[inline]
+    pub fn rescale(&self, from: Ppqn, to: Ppqn, rounding: Rounding) -> Option<Rescaled> {
+        ticks_from_ppqn(self.ticks(), from, to, rounding)
+            .map(|(ticks, error)| Rescaled { ticks: Ticks(ticks), error })
+    }
+    /// Convert to the equivalent `Ticks` at the `to` resolution.
+    ///
+    /// Returns `None` if `from` is `0` or if the result overflows.
+    #[inline]
+    pub fn to_ppqn(&self, from: Ppqn, to: Ppqn, rounding: Rounding) -> Option<Ticks> {
+        self.rescale(from, to, rounding).map(|rescaled| rescaled.ticks)
+    }
+
+    /// Checked addition. Returns `None` if overflow occurred.
+    #[inline]
+    pub fn checked_add(&self, rhs: Self) -> Option<Self> {
+        self.ticks().checked_add(rhs.ticks()).map(Self)
+    }
+    /// Checked subtraction. Returns `None` if overflow occurred.
+    #[inline]
+    pub fn checked_sub(&self, rhs: Self) -> Option<Self> {
+        self.ticks().checked_sub(rhs.ticks()).map(Self)
+    }
+    /// Checked multiplication by a scalar. Returns `None` if overflow occurred.
+    #[inline]
+    pub fn checked_mul(&self, rhs: calc::Ticks) -> Option<Self> {
+        self.ticks().checked_mul(rhs).map(Self)
+    }
+    /// Checked division by a scalar. Returns `None` if `rhs == 0` or overflow occurred.
+    #[inline]
+    pub fn checked_div(&self, rhs: calc::Ticks) -> Option<Self> {
+        self.ticks().checked_div(rhs).map(Self)
+    }
+
+    /// Saturating addition. Clamps the result at the numeric bounds instead of overflowing.
+    #[inline]
+    pub fn saturating_add(&self, rhs: Self) -> Self {
+        Self(self.ticks().saturating_add(rhs.ticks()))
+    }
+    /// Saturating subtraction. Clamps the result at the numeric bounds instead of overflowing.
+    #[inline]
+    pub fn saturating_sub(&self, rhs: Self) -> Self {
+        Self(self.ticks().saturating_sub(rhs.ticks()))
+    }
+    /// Saturating multiplication by a scalar. Clamps the result at the numeric bounds instead of
+    /// overflowing.
+    #[inline]
+    pub fn saturating_mul(&self, rhs: calc::Ticks) -> Self {
+        Self(self.ticks().saturating_mul(rhs))
+    }
+
+}
+
+/// The result of rescaling `Ticks` from one `Ppqn` to another.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Rescaled {
+    /// The rescaled ticks.
+    pub ticks: Ticks,
+    /// The amount lost to rounding as a fraction of a tick at the target resolution.
+    ///
+    /// This is the exact rescaled value minus `ticks`.
+    pub error: f64,
+}
+
+impl Rescaled {
+
+    /// Whether or not the rescaled ticks represent the original duration exactly.
+    #[inline]
+    pub fn is_exact(&self) -> bool { self.error == 0.0 }
+
 }
 
 impl From<calc::Ticks> for Ticks {
@@ -90,19 +170,35 @@ impl Sub for Ticks {
     }
 }
 
-impl Mul for Ticks {
+impl Mul<calc::Ticks> for Ticks {
     type Output = Self;
     #[inline]
-    fn mul(self, rhs: Self) -> Ticks {
-        Ticks(self.ticks() * rhs.ticks())
+    fn mul(self, rhs: calc::Ticks) -> Self {
+        Self(self.ticks() * rhs)
     }
 }
 
-impl Div for Ticks {
+impl Mul<Ticks> for calc::Ticks {
+    type Output = Ticks;
+    #[inline]
+    fn mul(self, rhs: Ticks) -> Ticks {
+        Ticks(self * rhs.ticks())
+    }
+}
+
+impl Div<calc::Ticks> for Ticks {
     type Output = Self;
     #[inline]
-    fn div(self, rhs: Self) -> Ticks {
-        Ticks(self.ticks() / rhs.ticks())
+    fn div(self, rhs: calc::Ticks) -> Self {
+        Self(self.ticks() / rhs)
+    }
+}
+
+impl Div for Ticks {
+    type Output = f64;
+    #[inline]
+    fn div(self, rhs: Self) -> f64 {
+        self.ticks() as f64 / rhs.ticks() as f64
     }
 }
 
@@ -134,14 +230,14 @@ impl SubAssign for Ticks {
     }
 }
 
-impl MulAssign for Ticks {
-    fn mul_assign(&mut self, rhs: Self) {
+impl MulAssign<calc::Ticks> for Ticks {
+    fn mul_assign(&mut self, rhs: calc::Ticks) {
         *self = *self * rhs;
     }
 }
 
-impl DivAssign for Ticks {
-    fn div_assign(&mut self, rhs: Self) {
+impl DivAssign<calc::Ticks> for Ticks {
+    fn div_assign(&mut self, rhs: calc::Ticks) {
         *self = *self / rhs;
     }
 }
@@ -152,6 +248,18 @@ impl RemAssign for Ticks {
     }
 }
 
+impl Sum for Ticks {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Ticks(0), |total, x| total + x)
+    }
+}
+
+impl<'a> Sum<&'a Ticks> for Ticks {
+    fn sum<I: Iterator<Item = &'a Ticks>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
 impl ToPrimitive for Ticks {
     fn to_u64(&self) -> Option<u64> {
         self.ticks().to_u64()
@@ -169,3 +277,172 @@ impl FromPrimitive for Ticks {
         Some(Ticks(n as calc::Ticks))
     }
 }
+
+/// `Ticks` that carry the `Ppqn` resolution they were measured at.
+///
+/// Arithmetic and comparisons between values of differing resolutions are performed at their
+/// common resolution, so resolutions can never be mixed by mistake. The resolution is never `0`,
+/// so that every value is a well defined fraction of a quarter note.
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PpqnTicks {
+    pub ticks: Ticks,
+    pub ppqn: NonZeroU32,
+}
+
+impl PpqnTicks {
+
+    /// Construct from a number of ticks at the given resolution.
+    #[inline]
+    pub fn new(ticks: Ticks, ppqn: NonZeroU32) -> PpqnTicks {
+        PpqnTicks { ticks, ppqn }
+    }
+
+    /// Construct from a number of ticks at the given resolution. Returns `None` if `ppqn` is `0`.
+    #[inline]
+    pub fn from_ppqn(ticks: Ticks, ppqn: Ppqn) -> Option<PpqnTicks> {
+        NonZeroU32::new(ppqn).map(|ppqn| PpqnTicks::new(ticks, ppqn))
+    }
+
+    /// The resolution as a `Ppqn`.
+    #[inline]
+    pub fn ppqn(&self) -> Ppqn { self.ppqn.get() }
+
+    /// Convert to the equivalent duration as a number of Beats.
+    #[inline]
+    pub fn beats(&self) -> f64 {
+        self.ticks.beats(self.ppqn())
+    }
+
+    /// Convert to the equivalent duration as a number of Bars.
+    #[inline]
+    pub fn bars(&self, ts: TimeSig) -> f64 {
+        self.ticks.bars(ts, self.ppqn())
+    }
+
+    /// Convert to `Ms`.
+    #[inline]
+    pub fn to_ms(&self, bpm: impl Into<Bpm>) -> Ms {
+        self.ticks.to_ms(bpm, self.ppqn())
+    }
+
+    /// Convert to `Samples`.
+    #[inline]
+    pub fn to_samples(&self, bpm: impl Into<Bpm>, sample_hz: SampleHz) -> Samples {
+        self.ticks.to_samples(bpm, self.ppqn(), sample_hz)
+    }
+
+    /// Rescale to the `to` resolution, reporting any loss.
+    ///
+    /// Returns `None` if the result overflows.
+    #[inline]
+    pub fn rescale(&self, to: Ppqn, rounding: Rounding) -> Option<Rescaled> {
+        self.ticks.rescale(self.ppqn(), to, rounding)
+    }
+    /// Convert to the equivalent `PpqnTicks` at the `to` resolution.
+    ///
+    /// Returns `None` if the result overflows.
+    #[inline]
+    pub fn to_ppqn(&self, to: NonZeroU32, rounding: Rounding) -> Option<PpqnTicks> {
+        self.ticks.to_ppqn(self.ppqn(), to.get(), rounding).map(|ticks| PpqnTicks::new(ticks, to))
+    }
+
+    /// Checked addition at the common resolution. Returns `None` if the common resolution or
+    /// the sum overflows.
+    #[inline]
+    pub fn checked_add(&self, rhs: Self) -> Option<Self> {
+        let (a, b, ppqn) = self.to_common(rhs)?;
+        a.checked_add(b).map(|ticks| PpqnTicks::new(ticks, ppqn))
+    }
+    /// Saturating addition at the common resolution. Clamps the result at the numeric bounds
+    /// instead of overflowing. Returns `None` if the common resolution overflows.
+    #[inline]
+    pub fn saturating_add(&self, rhs: Self) -> Option<Self> {
+        let (a, b, ppqn) = self.to_common(rhs)?;
+        Some(PpqnTicks::new(a.saturating_add(b), ppqn))
+    }
+    /// Checked subtraction at the common resolution. Returns `None` if the common resolution or
+    /// the difference overflows.
+    #[inline]
+    pub fn checked_sub(&self, rhs: Self) -> Option<Self> {
+        let (a, b, ppqn) = self.to_common(rhs)?;
+        a.checked_sub(b).map(|ticks| PpqnTicks::new(ticks, ppqn))
+    }
+    /// Saturating subtraction at the common resolution. Clamps the result at the numeric bounds
+    /// instead of overflowing. Returns `None` if the common resolution overflows.
+    #[inline]
+    pub fn saturating_sub(&self, rhs: Self) -> Option<Self> {
+        let (a, b, ppqn) = self.to_common(rhs)?;
+        Some(PpqnTicks::new(a.saturating_sub(b), ppqn))
+    }
+
+    /// Rescale both values to their common resolution without loss.
+    ///
+    /// Returns `None` if the common resolution overflows `Ppqn` or either value overflows `Ticks`.
+    fn to_common(self, other: PpqnTicks) -> Option<(Ticks, Ticks, NonZeroU32)> {
+        let ppqn = common_ppqn(&[self.ppqn(), other.ppqn()]).and_then(NonZeroU32::new)?;
+        let a = self.ticks.to_ppqn(self.ppqn(), ppqn.get(), Rounding::Nearest)?;
+        let b = other.ticks.to_ppqn(other.ppqn(), ppqn.get(), Rounding::Nearest)?;
+        Some((a, b, ppqn))
+    }
+
+}
+
+impl Add for PpqnTicks {
+    type Output = Self;
+    /// **Panics** if the common resolution or the sum overflows. See `checked_add`.
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        self.checked_add(rhs).expect("attempt to add `PpqnTicks` with overflow")
+    }
+}
+
+impl Sub for PpqnTicks {
+    type Output = Self;
+    /// **Panics** if the common resolution or the difference overflows. See `checked_sub`.
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        self.checked_sub(rhs).expect("attempt to subtract `PpqnTicks` with overflow")
+    }
+}
+
+impl Neg for PpqnTicks {
+    type Output = Self;
+    #[inline]
+    fn neg(self) -> Self {
+        PpqnTicks::new(-self.ticks, self.ppqn)
+    }
+}
+
+impl PartialEq for PpqnTicks {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for PpqnTicks {}
+
+impl PartialOrd for PpqnTicks {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PpqnTicks {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let a = self.ticks.ticks() as i128 * other.ppqn() as i128;
+        let b = other.ticks.ticks() as i128 * self.ppqn() as i128;
+        a.cmp(&b)
+    }
+}
+
+impl Hash for PpqnTicks {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Hash the reduced fraction so that equal durations at different resolutions match.
+        let ticks = self.ticks.ticks() as i128;
+        let ppqn = self.ppqn() as i128;
+        let gcd = num::integer::gcd(ticks, ppqn);
+        (ticks / gcd).hash(state);
+        (ppqn / gcd).hash(state);
+    }
+}
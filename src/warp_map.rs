@@ -0,0 +1,179 @@
+//! Warp markers mapping positions within an audio clip to musical positions within a song.
+
+use std::error::Error;
+use std::fmt;
+use crate::calc;
+use crate::{
+    Bpm,
+    Ppqn,
+    SampleHz,
+    Samples,
+    Ticks,
+    SECOND_IN_MS,
+    tick_in_ms,
+};
+
+/// Pins a position within an audio clip to a musical position within the song.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct WarpMarker {
+    pub samples: Samples,
+    pub ticks: Ticks,
+}
+
+/// The stretch applied to the audio between two neighbouring markers.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct WarpSegment {
+    pub start: WarpMarker,
+    pub end: WarpMarker,
+    /// The factor by which the audio within the segment is lengthened when the song plays at the
+    /// clip's native tempo. Values above `1.0` slow the audio down.
+    pub stretch: f64,
+}
+
+/// The reasons an edit to a `WarpMap` may be rejected.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum WarpError {
+    /// The marker would not be strictly after its predecessor and before its successor in both
+    /// `Samples` and `Ticks`.
+    NotMonotonic,
+    /// There is no marker at the given index.
+    NoSuchMarker,
+}
+
+impl fmt::Display for WarpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match *self {
+            WarpError::NotMonotonic => "warp markers must increase in both samples and ticks",
+            WarpError::NoSuchMarker => "no warp marker at the given index",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl Error for WarpError {}
+
+/// A piecewise linear mapping between positions within an audio clip and musical positions within
+/// the song, defined by a set of `WarpMarker`s.
+///
+/// Positions between markers are interpolated. Positions beyond the first or last marker follow
+/// the neighbouring segment, or the clip's native tempo when there are fewer than two markers.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct WarpMap {
+    /// The number of samples per tick at the clip's native tempo.
+    samples_per_tick: f64,
+    markers: Vec<WarpMarker>,
+}
+
+impl WarpMap {
+
+    /// Construct a map without markers for a clip recorded at the given native tempo.
+    pub fn new(bpm: impl Into<Bpm>, ppqn: Ppqn, sample_hz: SampleHz) -> WarpMap {
+        WarpMap {
+            samples_per_tick: tick_in_ms(bpm, ppqn) * sample_hz / SECOND_IN_MS,
+            markers: Vec::new(),
+        }
+    }
+
+    /// The markers, ordered by position.
+    #[inline]
+    pub fn markers(&self) -> &[WarpMarker] { &self.markers }
+
+    /// Insert a marker, returning its index.
+    pub fn insert(&mut self, marker: WarpMarker) -> Result<usize, WarpError> {
+        let index = self.markers.partition_point(|m| m.samples < marker.samples);
+        if !self.fits(index, index, marker) {
+            return Err(WarpError::NotMonotonic);
+        }
+        self.markers.insert(index, marker);
+        Ok(index)
+    }
+
+    /// Move the marker at the given index to a new position.
+    ///
+    /// The marker may not be moved past either of its neighbours.
+    pub fn move_marker(&mut self, index: usize, marker: WarpMarker) -> Result<(), WarpError> {
+        if index >= self.markers.len() {
+            return Err(WarpError::NoSuchMarker);
+        }
+        if !self.fits(index, index + 1, marker) {
+            return Err(WarpError::NotMonotonic);
+        }
+        self.markers[index] = marker;
+        Ok(())
+    }
+
+    /// Remove the marker at the given index.
+    pub fn remove(&mut self, index: usize) -> Result<WarpMarker, WarpError> {
+        if index >= self.markers.len() {
+            return Err(WarpError::NoSuchMarker);
+        }
+        Ok(self.markers.remove(index))
+    }
+
+    /// The segments between each pair of neighbouring markers.
+    pub fn segments(&self) -> impl Iterator<Item = WarpSegment> + '_ {
+        self.markers.windows(2).map(move |pair| {
+            let (start, end) = (pair[0], pair[1]);
+            let ticks = (end.ticks - start.ticks).ticks() as f64;
+            let samples = (end.samples - start.samples).samples() as f64;
+            WarpSegment { start, end, stretch: ticks * self.samples_per_tick / samples }
+        })
+    }
+
+    /// The fractional musical position of the given position within the clip.
+    pub fn ticks_at(&self, samples: Samples) -> f64 {
+        let (a, samples_per_tick) = self.segment(|m| m.samples <= samples);
+        a.ticks.ticks() as f64 + (samples - a.samples).samples() as f64 / samples_per_tick
+    }
+    /// The fractional position within the clip of the given musical position.
+    pub fn samples_at(&self, ticks: f64) -> f64 {
+        let (a, samples_per_tick) = self.segment(|m| m.ticks.ticks() as f64 <= ticks);
+        a.samples.samples() as f64 + (ticks - a.ticks.ticks() as f64) * samples_per_tick
+    }
+
+    /// Convert a position within the clip to the nearest musical position.
+    #[inline]
+    pub fn to_ticks(&self, samples: Samples) -> Ticks {
+        Ticks(self.ticks_at(samples).round() as calc::Ticks)
+    }
+    /// Convert a musical position to the nearest position within the clip.
+    #[inline]
+    pub fn to_samples(&self, ticks: Ticks) -> Samples {
+        Samples(self.samples_at(ticks.ticks() as f64).round() as calc::Samples)
+    }
+
+    /// Whether or not `marker` fits between the marker before `before` and the marker at `after`.
+    fn fits(&self, before: usize, after: usize, marker: WarpMarker) -> bool {
+        let prev_ok = before == 0 || {
+            let prev = self.markers[before - 1];
+            prev.samples < marker.samples && prev.ticks < marker.ticks
+        };
+        let next_ok = after >= self.markers.len() || {
+            let next = self.markers[after];
+            marker.samples < next.samples && marker.ticks < next.ticks
+        };
+        prev_ok && next_ok
+    }
+
+    /// The marker beginning the segment containing the position for which `is_before` returns
+    /// `true` for all earlier markers, along with the segment's samples per tick.
+    fn segment<F>(&self, is_before: F) -> (WarpMarker, f64)
+        where F: Fn(&WarpMarker) -> bool,
+    {
+        let origin = WarpMarker { samples: Samples(0), ticks: Ticks(0) };
+        match self.markers.len() {
+            0 => (origin, self.samples_per_tick),
+            1 => (self.markers[0], self.samples_per_tick),
+            n => {
+                let i = self.markers.partition_point(is_before).clamp(1, n - 1) - 1;
+                let (a, b) = (self.markers[i], self.markers[i + 1]);
+                let samples = (b.samples - a.samples).samples() as f64;
+                let ticks = (b.ticks - a.ticks).ticks() as f64;
+                (a, samples / ticks)
+            },
+        }
+    }
+
+}